@@ -118,7 +118,7 @@ fn advertise_mdns(opts: &DaemonOpts) -> Result<()> {
     fn pick_local_ipv4() -> Option<String> {
         use std::net::{SocketAddr, UdpSocket};
         let sock = UdpSocket::bind("0.0.0.0:0").ok()?;
-        let _ = sock.connect("8.8.8.8:80").ok()?;
+        sock.connect("8.8.8.8:80").ok()?;
         let addr: SocketAddr = sock.local_addr().ok()?;
         Some(addr.ip().to_string())
     }
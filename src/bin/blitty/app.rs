@@ -568,25 +568,25 @@ pub fn run(remote: Option<RemoteDest>) -> Result<()> {
                                 let _ = options::save_options(&app.options);
                                 app.ui_mode = UiMode::Normal;
                             }
-                            KeyCode::Left if modifiers.contains(KeyModifiers::CONTROL) => {
-                                if app.options_tab > 0 {
-                                    app.options_tab -= 1;
-                                    app.options_cursor = 0;
-                                }
+                            KeyCode::Left
+                                if modifiers.contains(KeyModifiers::CONTROL) && app.options_tab > 0 =>
+                            {
+                                app.options_tab -= 1;
+                                app.options_cursor = 0;
                             }
-                            KeyCode::Right if modifiers.contains(KeyModifiers::CONTROL) => {
-                                if app.options_tab < 7 {
-                                    app.options_tab += 1;
-                                    app.options_cursor = 0;
-                                }
+                            KeyCode::Right
+                                if modifiers.contains(KeyModifiers::CONTROL) && app.options_tab < 7 =>
+                            {
+                                app.options_tab += 1;
+                                app.options_cursor = 0;
                             }
+                            KeyCode::Left | KeyCode::Right
+                                if modifiers.contains(KeyModifiers::CONTROL) => {}
                             KeyCode::Char('a') if modifiers.contains(KeyModifiers::CONTROL) => {
                                 app.show_advanced = !app.show_advanced;
                             }
-                            KeyCode::Up => {
-                                if app.options_cursor > 0 {
-                                    app.options_cursor -= 1;
-                                }
+                            KeyCode::Up if app.options_cursor > 0 => {
+                                app.options_cursor -= 1;
                             }
                             KeyCode::Down => {
                                 // Determine max rows by peeking at current UI items length via logical index hack not possible; clamp later on render transitions.
@@ -795,23 +795,16 @@ pub fn run(remote: Option<RemoteDest>) -> Result<()> {
                             KeyCode::Enter => {
                                 let text = app.input_buffer.trim().to_string();
                                 match app.input_kind {
-                                    Some(InputKind::AddExcludeFile) => {
-                                        if !text.is_empty() {
-                                            app.options.exclude_files.push(text);
-                                        }
+                                    Some(InputKind::AddExcludeFile) if !text.is_empty() => {
+                                        app.options.exclude_files.push(text);
                                     }
-                                    Some(InputKind::AddExcludeDir) => {
-                                        if !text.is_empty() {
-                                            app.options.exclude_dirs.push(text);
-                                        }
+                                    Some(InputKind::AddExcludeDir) if !text.is_empty() => {
+                                        app.options.exclude_dirs.push(text);
                                     }
-                                    Some(InputKind::SetLogFile) => {
-                                        if !text.is_empty() {
-                                            app.options.log_file =
-                                                Some(std::path::PathBuf::from(text));
-                                        }
+                                    Some(InputKind::SetLogFile) if !text.is_empty() => {
+                                        app.options.log_file = Some(std::path::PathBuf::from(text));
                                     }
-                                    None => {}
+                                    _ => {}
                                 }
                                 let _ = options::save_options(&app.options);
                                 app.input_buffer.clear();
@@ -834,11 +827,9 @@ pub fn run(remote: Option<RemoteDest>) -> Result<()> {
                     } else {
                         // Normal mode
                         match (code, modifiers) {
-                            (KeyCode::PageUp, _) => {
-                                if app.log_scroll < app.log.len() {
-                                    app.log_scroll = app.log_scroll.saturating_add(5);
-                                    app.log_follow = false;
-                                }
+                            (KeyCode::PageUp, _) if app.log_scroll < app.log.len() => {
+                                app.log_scroll = app.log_scroll.saturating_add(5);
+                                app.log_follow = false;
                             }
                             (KeyCode::PageDown, _) => {
                                 app.log_scroll = app.log_scroll.saturating_sub(5);
@@ -1143,7 +1143,7 @@ fn draw_header(f: &mut Frame, area: Rect, app: &AppState) {
             ),
             Span::styled(
                 format!("{} discovered", app.discovered.len()),
-                ratatui::style::Style::default().fg(if app.discovered.len() > 0 {
+                ratatui::style::Style::default().fg(if !app.discovered.is_empty() {
                     Theme::GREEN()
                 } else {
                     Theme::COMMENT()
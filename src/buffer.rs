@@ -5,6 +5,8 @@ use parking_lot::Mutex;
 pub struct BufferSizer {
     max_buffer_size: usize,
     min_buffer_size: usize,
+    /// User override (`--io-buffer`); bypasses the heuristics entirely
+    fixed: Option<usize>,
     cached_available_memory: Mutex<Option<u64>>,
 }
 
@@ -13,10 +15,23 @@ impl BufferSizer {
         BufferSizer {
             max_buffer_size: 16 * 1024 * 1024, // 16MB max
             min_buffer_size: 1024 * 1024,      // 1MB min
+            fixed: None,
             cached_available_memory: Mutex::new(None),
         }
     }
 
+    /// Always use `size`, whatever the file size or medium
+    pub fn fixed(size: usize) -> Self {
+        BufferSizer {
+            fixed: Some(size),
+            ..Self::new()
+        }
+    }
+
+    pub fn fixed_size(&self) -> Option<usize> {
+        self.fixed
+    }
+
     /// Get available memory using sysinfo
     fn get_available_memory() -> u64 {
         use sysinfo::System;
@@ -27,6 +42,9 @@ impl BufferSizer {
 
     /// Calculate optimal buffer size based on file size and available memory
     pub fn calculate_buffer_size(&self, file_size: u64, is_network: bool) -> usize {
+        if let Some(size) = self.fixed {
+            return size;
+        }
         // Get or cache available memory
         let available_memory = {
             let mut cached = self.cached_available_memory.lock();
@@ -81,4 +99,13 @@ mod tests {
         assert!(local_buf >= 64 * 1024);
         assert!(local_buf <= 8 * 1024 * 1024);
     }
+
+    #[test]
+    fn test_fixed_size_overrides_heuristics() {
+        let sizer = BufferSizer::fixed(256 * 1024);
+        assert_eq!(sizer.fixed_size(), Some(256 * 1024));
+        for (size, network) in [(0, false), (50 << 20, true), (10 << 30, false)] {
+            assert_eq!(sizer.calculate_buffer_size(size, network), 256 * 1024);
+        }
+    }
 }
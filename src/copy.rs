@@ -3,19 +3,29 @@
 
 use crate::logger::Logger;
 use anyhow::{Context, Result};
-use parking_lot::Mutex;
+use parking_lot::{Condvar, Mutex};
 use rayon::prelude::*;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::buffer::BufferSizer;
 use crate::fs_enum::FileEntry;
 
 /// Check if a file needs to be copied (for mirror mode)
-pub fn file_needs_copy(src: &Path, dst: &Path, use_checksum: bool) -> Result<bool> {
+///
+/// A source counts as newer only when its mtime is more than `mtime_slack` ahead, so targets
+/// that round timestamps (FAT to 2 seconds, NTFS to 100ns) are not recopied on every run.
+/// With `checksum` set, same-size pairs are compared by content, read in chunks of that size.
+pub fn file_needs_copy(
+    src: &Path,
+    dst: &Path,
+    checksum: Option<usize>,
+    mtime_slack: Duration,
+) -> Result<bool> {
     // If destination doesn't exist, definitely copy
     if !dst.exists() {
         return Ok(true);
@@ -29,36 +39,123 @@ pub fn file_needs_copy(src: &Path, dst: &Path, use_checksum: bool) -> Result<boo
         return Ok(true);
     }
 
-    if use_checksum {
+    if let Some(buffer_size) = checksum {
         // Checksum comparison (slower but accurate)
-        Ok(files_have_different_content(src, dst)?)
+        Ok(files_have_different_content(src, dst, buffer_size)?)
     } else {
         // Fast timestamp comparison (default)
         let src_time = src_meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
         let dst_time = dst_meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
 
-        // Copy if source is newer (allow 2 second tolerance for filesystem precision)
+        // Copy if source is newer
         Ok(src_time
             .duration_since(dst_time)
-            .is_ok_and(|diff| diff.as_secs() > 2))
+            .is_ok_and(|diff| diff >= mtime_slack.max(Duration::from_nanos(1))))
+    }
+}
+
+/// Finest mtime step the filesystem holding `dir` keeps, found by writing a probe file with
+/// an odd-second timestamp and seeing how far it was rounded, in either direction.
+///
+/// Returns zero (exact) when the probe cannot be written.
+pub fn probe_mtime_granularity(dir: &Path) -> Duration {
+    let wanted = filetime::FileTime::from_unix_time(1_500_000_001, 123_456_789);
+    let Ok(probe) = tempfile::Builder::new()
+        .prefix(".blit-mtime-probe")
+        .tempfile_in(dir)
+    else {
+        return Duration::ZERO;
+    };
+    if filetime::set_file_mtime(probe.path(), wanted).is_err() {
+        return Duration::ZERO;
+    }
+    let Ok(md) = probe.as_file().metadata() else {
+        return Duration::ZERO;
+    };
+    mtime_step(wanted, filetime::FileTime::from_last_modification_time(&md))
+}
+
+/// Estimate of [`probe_mtime_granularity`] from mtimes already on the destination, for dry runs
+/// that must not write. The coarsest step every sampled timestamp sits on wins (all even seconds
+/// means 2 seconds); with nothing to sample the comparison stays exact.
+pub fn infer_mtime_granularity(files: impl IntoIterator<Item = PathBuf>) -> Duration {
+    const SAMPLE: usize = 64;
+    const STEPS: [Duration; 6] = [
+        Duration::from_secs(2),
+        Duration::from_secs(1),
+        Duration::from_millis(10),
+        Duration::from_millis(1),
+        Duration::from_micros(1),
+        Duration::from_nanos(100),
+    ];
+    let stamps: Vec<i128> = files
+        .into_iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .take(SAMPLE)
+        .map(|md| {
+            let t = filetime::FileTime::from_last_modification_time(&md);
+            i128::from(t.unix_seconds()) * 1_000_000_000 + i128::from(t.nanoseconds())
+        })
+        .collect();
+    if stamps.is_empty() {
+        return Duration::ZERO;
+    }
+    STEPS
+        .into_iter()
+        .find(|step| {
+            let step = step.as_nanos() as i128;
+            stamps.iter().all(|stamp| stamp.rem_euclid(step) == 0)
+        })
+        .unwrap_or(Duration::ZERO)
+}
+
+/// The step implied by `wanted` being stored as `got`.
+///
+/// The probe's sub-second digits are at least one smaller step away from every grid line, so
+/// the size of the error picks the step whether the filesystem truncates or rounds up. Whole
+/// seconds are told apart by parity instead: 2-second filesystems (FAT) keep only even ones.
+fn mtime_step(wanted: filetime::FileTime, got: filetime::FileTime) -> Duration {
+    const STEPS: [Duration; 5] = [
+        Duration::from_nanos(100),
+        Duration::from_micros(1),
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+        Duration::from_secs(1),
+    ];
+    let nanos = |t: filetime::FileTime| {
+        i128::from(t.unix_seconds()) * 1_000_000_000 + i128::from(t.nanoseconds())
+    };
+    let lost = (nanos(wanted) - nanos(got)).unsigned_abs();
+    if lost == 0 {
+        return Duration::ZERO;
+    }
+    if got.nanoseconds() == 0 {
+        return Duration::from_secs(if got.unix_seconds() % 2 == 0 { 2 } else { 1 });
+    }
+    match STEPS.iter().find(|step| lost < step.as_nanos()) {
+        Some(&step) => step,
+        None => Duration::from_secs(2),
     }
 }
 
+/// Read size for `--checksum` comparisons when `--io-buffer` does not set one
+pub const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
 /// Compare file contents using fast hashing (for --checksum mode)
-fn files_have_different_content(src: &Path, dst: &Path) -> Result<bool> {
-    let src_hash = hash_file_content(src)?;
-    let dst_hash = hash_file_content(dst)?;
+fn files_have_different_content(src: &Path, dst: &Path, buffer_size: usize) -> Result<bool> {
+    let mut buffer = vec![0u8; buffer_size];
+    let src_hash = hash_file_content(src, &mut buffer)?;
+    let dst_hash = hash_file_content(dst, &mut buffer)?;
     Ok(src_hash != dst_hash)
 }
 
 /// Fast file content hashing using BLAKE3
-fn hash_file_content(path: &Path) -> Result<[u8; 32]> {
+fn hash_file_content(path: &Path, buffer: &mut [u8]) -> Result<[u8; 32]> {
     let mut hasher = blake3::Hasher::new();
-    let mut buffer = [0u8; 64 * 1024]; // 64KB chunks
     let mut file = File::open(path)?;
 
     loop {
-        let bytes_read = file.read(&mut buffer)?;
+        let bytes_read = file.read(buffer)?;
         if bytes_read == 0 {
             break;
         }
@@ -73,7 +170,16 @@ fn hash_file_content(path: &Path) -> Result<[u8; 32]> {
 pub struct CopyStats {
     pub files_copied: u64,
     pub bytes_copied: u64,
+    /// Files left alone because the destination was already up to date
+    pub files_skipped: u64,
+    /// Files hard linked from `--link-dest` instead of copied
+    pub files_linked: u64,
+    /// Destination directories this run had to create, and those that were already there
+    pub dirs_created: u64,
+    pub dirs_existing: u64,
     pub errors: Vec<String>,
+    /// Wall-clock time spent producing these stats, so rates can be computed without a logger
+    pub elapsed: Duration,
 }
 
 impl CopyStats {
@@ -87,6 +193,278 @@ impl CopyStats {
     }
 }
 
+/// Access-time handling requested on the command line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AtimeOptions {
+    /// Give the destination the source's access time
+    pub preserve: bool,
+    /// Put the source's access time back after it has been read
+    pub restore_source: bool,
+}
+
+impl AtimeOptions {
+    pub fn any(&self) -> bool {
+        self.preserve || self.restore_source
+    }
+}
+
+/// Capture a file's access time before the copy reads it
+pub fn read_atime(path: &Path) -> Option<filetime::FileTime> {
+    fs::metadata(path)
+        .ok()
+        .map(|md| filetime::FileTime::from_last_access_time(&md))
+}
+
+/// Apply `opts` once `src` has been copied to `dst`; `src_atime` must predate the copy.
+///
+/// Only access times are touched, modification times are left as they are.
+pub fn apply_atime(
+    src: &Path,
+    dst: &Path,
+    src_atime: filetime::FileTime,
+    opts: AtimeOptions,
+) -> Result<()> {
+    if opts.preserve {
+        filetime::set_file_atime(dst, src_atime)
+            .with_context(|| format!("Failed to set access time on {:?}", dst))?;
+    }
+    if opts.restore_source {
+        filetime::set_file_atime(src, src_atime)
+            .with_context(|| format!("Failed to restore access time on {:?}", src))?;
+    }
+    Ok(())
+}
+
+/// Give the owner write permission on `path` (`--force`).
+///
+/// Returns the original permissions when they had to change, so the caller can restore them.
+#[cfg(unix)]
+pub fn add_owner_write(path: &Path) -> std::io::Result<Option<fs::Permissions>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let original = fs::metadata(path)?.permissions();
+    if original.mode() & 0o200 != 0 {
+        return Ok(None);
+    }
+    fs::set_permissions(path, fs::Permissions::from_mode(original.mode() | 0o200))?;
+    Ok(Some(original))
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+pub fn add_owner_write(path: &Path) -> std::io::Result<Option<fs::Permissions>> {
+    let original = fs::metadata(path)?.permissions();
+    if !original.readonly() {
+        return Ok(None);
+    }
+    let mut writable = original.clone();
+    writable.set_readonly(false);
+    fs::set_permissions(path, writable)?;
+    Ok(Some(original))
+}
+
+/// Flush a directory's entries to disk so newly created or removed names survive a crash
+/// (`--fsync-dirs`)
+#[cfg(unix)]
+pub fn sync_dir(path: &Path) -> std::io::Result<()> {
+    fs::File::open(path)?.sync_all()
+}
+
+/// Directory handles cannot be fsync'd through std on this platform; NTFS journals
+/// metadata itself, so this is a no-op.
+#[cfg(not(unix))]
+pub fn sync_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Bytes available to the current user on the filesystem holding `path` (or its nearest
+/// existing ancestor, so a destination that is not created yet can be checked)
+pub fn available_space(path: &Path) -> std::io::Result<u64> {
+    let existing = path
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or_else(|| Path::new("."));
+    available_space_impl(existing)
+}
+
+#[cfg(unix)]
+fn available_space_impl(path: &Path) -> std::io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut st) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(st.f_bavail as u64 * st.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space_impl(path: &Path) -> std::io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    Ok(available)
+}
+
+/// Fail if writing `size` bytes to `dst` would leave less than `min_free` bytes on its filesystem.
+///
+/// An existing destination is truncated before the write, so its current size counts as free.
+pub fn ensure_free_space(dst: &Path, size: u64, min_free: u64) -> Result<()> {
+    let available = available_space(dst)
+        .with_context(|| format!("Failed to query free space for {:?}", dst))?;
+    let existing = fs::metadata(dst).map(|m| m.len()).unwrap_or(0);
+    let needed = size.saturating_sub(existing).saturating_add(min_free);
+    if available < needed {
+        anyhow::bail!(
+            "Not enough free space for {:?}: {} bytes available, {} needed ({} for the file + {} --min-free)",
+            dst,
+            available,
+            needed,
+            needed - min_free,
+            min_free
+        );
+    }
+    Ok(())
+}
+
+/// Failure count shared by every copy worker, with an optional abort threshold.
+pub struct ErrorBudget {
+    max_errors: usize,
+    seen: AtomicUsize,
+}
+
+impl ErrorBudget {
+    /// Create a budget that trips after `max_errors` failures (0 = never)
+    pub fn new(max_errors: usize) -> Self {
+        ErrorBudget {
+            max_errors,
+            seen: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count one failed file
+    pub fn record(&self) {
+        self.seen.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Number of failures recorded so far
+    pub fn count(&self) -> usize {
+        self.seen.load(Ordering::SeqCst)
+    }
+
+    /// True once the threshold is reached; workers stop picking up new files
+    pub fn exhausted(&self) -> bool {
+        self.max_errors > 0 && self.count() >= self.max_errors
+    }
+}
+
+impl Default for ErrorBudget {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// External pause switch (`--control-file`).
+///
+/// While the file reads `pause`, workers wait before starting their next file; any other
+/// content, or no file at all, lets them continue. A file already being copied is finished.
+pub struct PauseControl {
+    path: Option<PathBuf>,
+    poll: Duration,
+}
+
+impl PauseControl {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            poll: Duration::from_millis(200),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.path.as_ref().is_some_and(|p| {
+            fs::read_to_string(p).is_ok_and(|s| s.trim().eq_ignore_ascii_case("pause"))
+        })
+    }
+
+    /// Block the calling worker until the control file no longer says `pause`
+    pub fn wait_while_paused(&self) {
+        while self.is_paused() {
+            std::thread::sleep(self.poll);
+        }
+    }
+}
+
+impl Default for PauseControl {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// Bounds how many files are held open at once, independent of the worker thread count.
+///
+/// Each copy holds a source and a destination handle, so it takes two slots.
+pub struct OpenFileLimiter {
+    max_open: usize,
+    open: Mutex<usize>,
+    released: Condvar,
+}
+
+impl OpenFileLimiter {
+    /// Create a limiter for `max_open` handles (0 = unlimited, values below 2 are raised to 2)
+    pub fn new(max_open: usize) -> Self {
+        OpenFileLimiter {
+            max_open: if max_open == 0 { 0 } else { max_open.max(2) },
+            open: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until a source+destination pair may be opened
+    pub fn acquire(&self) -> OpenFileGuard<'_> {
+        if self.max_open > 0 {
+            let mut open = self.open.lock();
+            while *open + 2 > self.max_open {
+                self.released.wait(&mut open);
+            }
+            *open += 2;
+        }
+        OpenFileGuard { limiter: self }
+    }
+}
+
+impl Default for OpenFileLimiter {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Releases the limiter slots when the copy finishes
+pub struct OpenFileGuard<'a> {
+    limiter: &'a OpenFileLimiter,
+}
+
+impl Drop for OpenFileGuard<'_> {
+    fn drop(&mut self) {
+        if self.limiter.max_open > 0 {
+            let mut open = self.limiter.open.lock();
+            *open -= 2;
+            self.limiter.released.notify_one();
+        }
+    }
+}
+
 /// Copy a single file with optimal buffer size
 pub fn copy_file(
     src: &Path,
@@ -171,8 +549,12 @@ pub fn parallel_copy_files(
     pairs: Vec<(FileEntry, PathBuf)>,
     buffer_sizer: Arc<BufferSizer>,
     is_network: bool,
+    limiter: &OpenFileLimiter,
+    errors: &ErrorBudget,
+    control: &PauseControl,
     logger: &dyn Logger,
 ) -> CopyStats {
+    let started = Instant::now();
     let stats = Arc::new(Mutex::new(CopyStats::default()));
 
     // Use rayon for parallel copying
@@ -180,12 +562,19 @@ pub fn parallel_copy_files(
         // Show progress for verbose mode
         // No progress display for maximum performance
 
+        if errors.exhausted() {
+            return;
+        }
+        // Paused workers must not hold open-file slots
+        control.wait_while_paused();
+        let _open = limiter.acquire();
         match copy_file(&entry.path, dst, &buffer_sizer, is_network, logger) {
             Ok(bytes) => {
                 let mut s = stats.lock();
                 s.add_file(bytes);
             }
             Err(e) => {
+                errors.record();
                 let mut s = stats.lock();
                 s.add_error(format!("Failed to copy {:?}: {}", entry.path, e));
             }
@@ -193,7 +582,7 @@ pub fn parallel_copy_files(
     });
 
     // Extract the stats from Arc<Mutex<CopyStats>>
-    Arc::try_unwrap(stats)
+    let mut stats = Arc::try_unwrap(stats)
         .map(|mutex| mutex.into_inner())
         .unwrap_or_else(|arc| {
             // Log when we fall back to cloning because Arc is still shared
@@ -201,7 +590,9 @@ pub fn parallel_copy_files(
                 "Warning: Arc<CopyStats> still has multiple references, falling back to clone"
             );
             arc.lock().clone()
-        })
+        });
+    stats.elapsed = started.elapsed();
+    stats
 }
 
 /// Memory-mapped copy for very large files (>100MB)
@@ -260,6 +651,51 @@ pub fn mmap_copy_file(src: &Path, dst: &Path) -> Result<u64> {
     std::fs::copy(src, dst).context("Copy failed")
 }
 
+/// Clone `src` to `dst` with `FICLONE`, sharing the data copy-on-write (btrfs, XFS, bcachefs).
+///
+/// The clone is made in a fresh file beside `dst`, given the source mtime and permission bits
+/// (as the tar path sets them) and only then renamed over `dst`. Returns `Ok(false)`, with any
+/// existing `dst` untouched, when the filesystem cannot clone or the paths are on different
+/// devices; the caller then copies normally.
+#[cfg(target_os = "linux")]
+pub fn reflink_file(src: &Path, dst: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::io::AsRawFd;
+
+    let src_file = File::open(src)?;
+    let parent = dst.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let clone = tempfile::Builder::new()
+        .prefix(".blit-clone")
+        .tempfile_in(parent)?;
+    let clone_fd = clone.as_file().as_raw_fd();
+    let rc = unsafe { libc::ioctl(clone_fd, libc::FICLONE, src_file.as_raw_fd()) };
+    if rc != 0 {
+        let err = std::io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::EOPNOTSUPP | libc::EXDEV | libc::EINVAL | libc::ENOTTY) => Ok(false),
+            _ => Err(err).with_context(|| format!("clone {:?} to {:?}", src, dst)),
+        };
+    }
+    let md = src_file.metadata()?;
+    let mode = md.permissions().mode() & 0o777;
+    clone
+        .as_file()
+        .set_permissions(fs::Permissions::from_mode(mode))?;
+    let mtime = filetime::FileTime::from_last_modification_time(&md);
+    filetime::set_file_handle_times(clone.as_file(), None, Some(mtime))?;
+    clone
+        .persist(dst)
+        .map_err(|e| e.error)
+        .with_context(|| format!("rename clone to {:?}", dst))?;
+    Ok(true)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn reflink_file(_src: &Path, _dst: &Path) -> Result<bool> {
+    Ok(false)
+}
+
 /// Chunked copy for large files (>10MB) with progress
 pub fn chunked_copy_file(
     src: &Path,
@@ -275,12 +711,12 @@ pub fn chunked_copy_file(
         let metadata = fs::metadata(src)?;
         let file_size = metadata.len();
 
-        // For very large files, use 16MB chunks
-        let chunk_size = if file_size > 1_073_741_824 {
+        // For very large files, use 16MB chunks unless the user picked a size
+        let chunk_size = match buffer_sizer.fixed_size() {
+            Some(size) => size,
             // > 1GB
-            16 * 1024 * 1024
-        } else {
-            buffer_sizer.calculate_buffer_size(file_size, is_network)
+            None if file_size > 1_073_741_824 => 16 * 1024 * 1024,
+            None => buffer_sizer.calculate_buffer_size(file_size, is_network),
         };
 
         // Create parent directory
@@ -365,3 +801,407 @@ pub fn windows_copyfile(src: &Path, dst: &Path) -> Result<u64> {
 pub fn windows_copyfile(src: &Path, dst: &Path) -> Result<u64> {
     fs::copy(src, dst).context("Failed to copy file")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_file_limiter_caps_concurrent_copies() {
+        let limiter = OpenFileLimiter::new(4); // two copies at a time
+        let open = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        (0..64).into_par_iter().for_each(|_| {
+            let _guard = limiter.acquire();
+            let now = open.fetch_add(2, Ordering::SeqCst) + 2;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            open.fetch_sub(2, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 4);
+        assert!(peak.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[test]
+    fn error_budget_stops_copies_after_max_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let max_errors = 3;
+        // One more missing source than the budget allows
+        let pairs: Vec<(FileEntry, PathBuf)> = (0..=max_errors)
+            .map(|i| {
+                let entry = FileEntry {
+                    path: tmp.path().join(format!("missing{}", i)),
+                    size: 1,
+                    is_directory: false,
+                };
+                (entry, tmp.path().join(format!("out{}", i)))
+            })
+            .collect();
+
+        let errors = ErrorBudget::new(max_errors);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let stats = pool.install(|| {
+            parallel_copy_files(
+                pairs,
+                Arc::new(BufferSizer::new()),
+                false,
+                &OpenFileLimiter::default(),
+                &errors,
+                &PauseControl::default(),
+                &crate::logger::NoopLogger,
+            )
+        });
+
+        assert!(errors.exhausted());
+        assert_eq!(errors.count(), max_errors);
+        assert_eq!(stats.errors.len(), max_errors);
+    }
+
+    #[test]
+    fn parallel_copy_records_elapsed_time() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("in");
+        fs::write(&src, vec![7u8; 64 * 1024]).unwrap();
+        let entry = FileEntry {
+            path: src,
+            size: 64 * 1024,
+            is_directory: false,
+        };
+
+        let stats = parallel_copy_files(
+            vec![(entry, tmp.path().join("out"))],
+            Arc::new(BufferSizer::new()),
+            false,
+            &OpenFileLimiter::default(),
+            &ErrorBudget::default(),
+            &PauseControl::default(),
+            &crate::logger::NoopLogger,
+        );
+
+        assert_eq!(stats.files_copied, 1);
+        assert!(stats.elapsed > Duration::ZERO);
+    }
+
+    #[test]
+    fn control_file_pauses_and_resumes_workers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let control_path = tmp.path().join("control");
+        fs::write(&control_path, "pause\n").unwrap();
+        let pairs: Vec<(FileEntry, PathBuf)> = (0..3)
+            .map(|i| {
+                let src = tmp.path().join(format!("in{}", i));
+                fs::write(&src, b"data").unwrap();
+                let entry = FileEntry {
+                    path: src,
+                    size: 4,
+                    is_directory: false,
+                };
+                (entry, tmp.path().join(format!("out{}", i)))
+            })
+            .collect();
+        let outputs: Vec<PathBuf> = pairs.iter().map(|(_, dst)| dst.clone()).collect();
+
+        let control = PauseControl {
+            path: Some(control_path.clone()),
+            poll: Duration::from_millis(10),
+        };
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(|| {
+                parallel_copy_files(
+                    pairs,
+                    Arc::new(BufferSizer::new()),
+                    false,
+                    &OpenFileLimiter::default(),
+                    &ErrorBudget::default(),
+                    &control,
+                    &crate::logger::NoopLogger,
+                )
+            });
+
+            std::thread::sleep(Duration::from_millis(200));
+            assert!(outputs.iter().all(|p| !p.exists()), "copied while paused");
+
+            fs::write(&control_path, "resume").unwrap();
+            let stats = worker.join().unwrap();
+            assert_eq!(stats.files_copied, 3);
+        });
+        assert!(outputs.iter().all(|p| p.exists()));
+    }
+
+    #[cfg(unix)]
+    fn atime_of(path: &Path) -> filetime::FileTime {
+        filetime::FileTime::from_last_access_time(&fs::metadata(path).unwrap())
+    }
+
+    #[cfg(unix)]
+    fn copy_with_atime(opts: AtimeOptions) -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("dst.bin");
+        fs::write(&src, b"archive payload").unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_atime(&src, old).unwrap();
+
+        let before = read_atime(&src).unwrap();
+        copy_file(
+            &src,
+            &dst,
+            &BufferSizer::new(),
+            false,
+            &crate::logger::NoopLogger,
+        )
+        .unwrap();
+        apply_atime(&src, &dst, before, opts).unwrap();
+        (tmp, src, dst)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserve_atime_copies_source_access_time() {
+        let opts = AtimeOptions {
+            preserve: true,
+            restore_source: false,
+        };
+        let (_tmp, _src, dst) = copy_with_atime(opts);
+        assert_eq!(atime_of(&dst).unix_seconds(), 1_000_000_000);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restore_src_atime_undoes_the_read() {
+        let opts = AtimeOptions {
+            preserve: false,
+            restore_source: true,
+        };
+        let (_tmp, src, dst) = copy_with_atime(opts);
+        assert_eq!(atime_of(&src).unix_seconds(), 1_000_000_000);
+        // The destination keeps whatever access time the copy gave it
+        assert!(atime_of(&dst).unix_seconds() > 1_000_000_000);
+    }
+
+    #[test]
+    fn copies_are_identical_across_fixed_buffer_sizes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        // Not a multiple of any buffer size below, so every copy ends on a partial read
+        let data: Vec<u8> = (0..300_007u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&src, &data).unwrap();
+
+        for size in [4 << 10, 64 << 10, 1 << 20] {
+            let sizer = BufferSizer::fixed(size);
+            let dst = tmp.path().join(format!("copy-{}.bin", size));
+            let bytes = copy_file(&src, &dst, &sizer, false, &crate::logger::NoopLogger).unwrap();
+            assert_eq!(bytes, data.len() as u64);
+            assert_eq!(fs::read(&dst).unwrap(), data);
+
+            let dst = tmp.path().join(format!("chunked-{}.bin", size));
+            chunked_copy_file(&src, &dst, &sizer, false, None, &crate::logger::NoopLogger).unwrap();
+            assert_eq!(fs::read(&dst).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn reflink_clones_or_declines_cleanly() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("sub/dst.bin");
+        fs::write(&src, b"shared extents").unwrap();
+
+        // Only CoW filesystems clone; elsewhere the caller must find no half-made file
+        if reflink_file(&src, &dst).unwrap() {
+            assert_eq!(fs::read(&dst).unwrap(), b"shared extents");
+        } else {
+            assert!(!dst.exists());
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reflink_sets_metadata_and_never_clobbers_on_decline() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("dst.bin");
+        fs::write(&src, b"new data").unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o640)).unwrap();
+        let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_mtime(&src, mtime).unwrap();
+        fs::write(&dst, b"existing").unwrap();
+
+        if reflink_file(&src, &dst).unwrap() {
+            let md = fs::metadata(&dst).unwrap();
+            assert_eq!(fs::read(&dst).unwrap(), b"new data");
+            assert_eq!(md.permissions().mode() & 0o777, 0o640);
+            assert_eq!(filetime::FileTime::from_last_modification_time(&md), mtime);
+        } else {
+            assert_eq!(fs::read(&dst).unwrap(), b"existing");
+        }
+        // No clone temp file is left behind either way
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn chunked_copy_feeds_byte_progress() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("big.bin");
+        let dst = tmp.path().join("out.bin");
+        fs::write(&src, vec![1u8; 256 * 1024]).unwrap();
+
+        // 64 chunks, so the bar moves throughout rather than jumping from 0 to done
+        let pb = indicatif::ProgressBar::hidden();
+        pb.set_length(256 * 1024);
+        let sizer = BufferSizer::fixed(4 << 10);
+        chunked_copy_file(
+            &src,
+            &dst,
+            &sizer,
+            false,
+            Some(&pb),
+            &crate::logger::NoopLogger,
+        )
+        .unwrap();
+
+        assert_eq!(pb.position(), 256 * 1024);
+    }
+
+    #[test]
+    fn checksum_mode_catches_same_size_same_mtime_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("dst.bin");
+        fs::write(&src, b"aaaa").unwrap();
+        fs::write(&dst, b"bbbb").unwrap();
+        let mtime = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(&src, mtime).unwrap();
+        filetime::set_file_mtime(&dst, mtime).unwrap();
+
+        // size+mtime cannot see the change; the checksum pass hashes same-size pairs only
+        assert!(!file_needs_copy(&src, &dst, None, Duration::ZERO).unwrap());
+        assert!(file_needs_copy(&src, &dst, Some(HASH_BUFFER_SIZE), Duration::ZERO).unwrap());
+
+        fs::write(&dst, b"aaaa").unwrap();
+        filetime::set_file_mtime(&dst, filetime::FileTime::from_unix_time(1, 0)).unwrap();
+        // mtime is ignored in checksum mode
+        assert!(!file_needs_copy(&src, &dst, Some(HASH_BUFFER_SIZE), Duration::ZERO).unwrap());
+        // and a buffer smaller than the file hashes it in several reads
+        assert!(!file_needs_copy(&src, &dst, Some(3), Duration::ZERO).unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn nanosecond_mtimes_round_trip_and_compare() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("dst.bin");
+        fs::write(&src, b"same").unwrap();
+        fs::write(&dst, b"same").unwrap();
+        let src_time = filetime::FileTime::from_unix_time(1_500_000_000, 123_456_789);
+        filetime::set_file_mtime(&src, src_time).unwrap();
+        filetime::set_file_mtime(&dst, src_time).unwrap();
+
+        let read_back = filetime::FileTime::from_last_modification_time(&dst.metadata().unwrap());
+        assert_eq!(read_back, src_time);
+        assert!(!file_needs_copy(&src, &dst, None, Duration::ZERO).unwrap());
+
+        // Microseconds behind: a change only nanosecond comparison can see
+        let older = filetime::FileTime::from_unix_time(1_500_000_000, 123_000_000);
+        filetime::set_file_mtime(&dst, older).unwrap();
+        assert!(file_needs_copy(&src, &dst, None, Duration::ZERO).unwrap());
+        assert!(!file_needs_copy(&src, &dst, None, Duration::from_secs(2)).unwrap());
+    }
+
+    #[test]
+    fn comparisons_allow_for_destination_granularity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("src.bin");
+        let dst = tmp.path().join("dst.bin");
+        fs::write(&src, b"same").unwrap();
+        fs::write(&dst, b"same").unwrap();
+        // What a FAT destination keeps of 1_500_000_001.999999999: the even second below it
+        let src_time = filetime::FileTime::from_unix_time(1_500_000_001, 999_999_999);
+        let fat_time = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(&src, src_time).unwrap();
+        filetime::set_file_mtime(&dst, fat_time).unwrap();
+
+        assert!(!file_needs_copy(&src, &dst, None, Duration::from_secs(2)).unwrap());
+        assert!(file_needs_copy(&src, &dst, None, Duration::from_secs(1)).unwrap());
+
+        // A real change is a whole step or more ahead
+        let later = filetime::FileTime::from_unix_time(1_500_000_002, 0);
+        filetime::set_file_mtime(&src, later).unwrap();
+        assert!(file_needs_copy(&src, &dst, None, Duration::from_secs(2)).unwrap());
+    }
+
+    #[test]
+    fn mtime_step_handles_rounding_either_way() {
+        let wanted = filetime::FileTime::from_unix_time(1_500_000_001, 123_456_789);
+        let step =
+            |secs, nanos| mtime_step(wanted, filetime::FileTime::from_unix_time(secs, nanos));
+        assert_eq!(step(1_500_000_001, 123_456_789), Duration::ZERO);
+        assert_eq!(step(1_500_000_001, 123_456_700), Duration::from_nanos(100));
+        assert_eq!(step(1_500_000_001, 124_000_000), Duration::from_millis(1));
+        assert_eq!(step(1_500_000_001, 130_000_000), Duration::from_millis(10));
+        assert_eq!(step(1_500_000_001, 0), Duration::from_secs(1));
+        // FAT: down to the even second below, or (Windows) up to the one above
+        assert_eq!(step(1_500_000_000, 0), Duration::from_secs(2));
+        assert_eq!(step(1_500_000_002, 0), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn granularity_inferred_from_existing_mtimes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stamped = |name: &str, secs, nanos| {
+            let path = tmp.path().join(name);
+            fs::write(&path, b"x").unwrap();
+            let t = filetime::FileTime::from_unix_time(secs, nanos);
+            filetime::set_file_mtime(&path, t).unwrap();
+            path
+        };
+        let fat = [
+            stamped("a", 1_500_000_000, 0),
+            stamped("b", 1_500_000_002, 0),
+        ];
+        assert_eq!(infer_mtime_granularity(fat.clone()), Duration::from_secs(2));
+        let odd = stamped("c", 1_500_000_001, 0);
+        assert_eq!(
+            infer_mtime_granularity([fat[0].clone(), odd]),
+            Duration::from_secs(1)
+        );
+        let fine = stamped("d", 1_500_000_000, 123_456_789);
+        assert_eq!(
+            infer_mtime_granularity([fat[0].clone(), fine]),
+            Duration::ZERO
+        );
+        // Missing destinations are not evidence either way
+        assert_eq!(
+            infer_mtime_granularity([tmp.path().join("none")]),
+            Duration::ZERO
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn probe_sees_nanosecond_timestamps_on_linux_tmp() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(probe_mtime_granularity(tmp.path()), Duration::ZERO);
+        // The probe file cleans up after itself
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn free_space_check_honours_margin() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dst = tmp.path().join("not/yet/created.bin");
+        assert!(available_space(&dst).unwrap() > 0);
+        ensure_free_space(&dst, 1, 0).unwrap();
+
+        let err = ensure_free_space(&dst, 1, u64::MAX / 2).unwrap_err();
+        assert!(err.to_string().contains("Not enough free space"), "{}", err);
+    }
+}
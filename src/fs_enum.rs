@@ -17,24 +17,79 @@ pub struct CopyJob {
     pub entry: FileEntry,
 }
 
+/// One `--filter` rule: `+ PATTERN` includes matching files, `- PATTERN` excludes them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterRule {
+    pub include: bool,
+    pub pattern: String,
+}
+
+impl std::str::FromStr for FilterRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (include, pattern) = if let Some(rest) = s.strip_prefix("+ ") {
+            (true, rest)
+        } else if let Some(rest) = s.strip_prefix("- ") {
+            (false, rest)
+        } else {
+            return Err(format!("expected '+ PATTERN' or '- PATTERN', got '{}'", s));
+        };
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(format!("filter rule '{}' has no pattern", s));
+        }
+        Ok(Self {
+            include,
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
 /// File filter options (robocopy-style compatibility)
 #[derive(Default)]
 pub struct FileFilter {
+    /// Ordered file rules; the first match decides, ahead of `exclude_files`
+    pub rules: Vec<FilterRule>,
     pub exclude_files: Vec<String>,
     pub exclude_dirs: Vec<String>,
     pub min_size: Option<u64>,
     pub max_size: Option<u64>,
+    /// Directory subtrees skipped outright (e.g. a destination nested inside the source)
+    pub exclude_paths: Vec<PathBuf>,
+    /// Files blit is writing during the run (the `--log-file`), never copied or purged
+    pub protected_files: Vec<PathBuf>,
+    /// Skip directories tagged with `CACHEDIR.TAG` or `.nobackup` (like tar/borg `--exclude-caches`)
+    pub exclude_caches: bool,
+    /// Do not descend into directories on a different filesystem than the root (rsync `-x`)
+    pub one_file_system: bool,
+    /// Fail the scan on an unreadable entry instead of warning and skipping it
+    pub strict_scan: bool,
+    /// Mirror deletions leave destination-only top-level dot-directories (`.git`, `.snapshots`)
+    pub keep_dest_dotdirs: bool,
 }
 
 impl FileFilter {
     /// Check if a file should be included
-    fn should_include_file(&self, path: &Path, size: u64) -> bool {
+    ///
+    /// `rel` is the path relative to the scan root. Patterns starting with `/` are anchored to
+    /// the root (gitignore-style); other patterns match the file name at any depth.
+    fn should_include_file(&self, path: &Path, rel: &Path, size: u64) -> bool {
+        if self.protected_files.iter().any(|p| p == path) {
+            return false;
+        }
         // Check file patterns
-        let filename = path.file_name().unwrap_or_default().to_string_lossy();
-        for pattern in &self.exclude_files {
-            if glob_match(pattern, &filename) {
-                return false;
-            }
+        let filename = rel.file_name().unwrap_or_default().to_string_lossy();
+        let matches = |pattern: &str| match pattern.strip_prefix('/') {
+            Some(anchored) => rel.components().count() == 1 && glob_match(anchored, &filename),
+            None => glob_match(pattern, &filename),
+        };
+        // --xf behaves like `- PATTERN` rules appended after the explicit ones
+        match self.rules.iter().find(|rule| matches(&rule.pattern)) {
+            Some(rule) if !rule.include => return false,
+            Some(_) => {}
+            None if self.exclude_files.iter().any(|p| matches(p)) => return false,
+            None => {}
         }
 
         // Check size limits
@@ -53,23 +108,105 @@ impl FileFilter {
     }
 
     /// Check if a directory should be included
-    fn should_include_dir(&self, path: &Path) -> bool {
+    ///
+    /// `path` is the walked path and `rel` the same path relative to the scan root; only `rel`
+    /// is matched against patterns so components of the root itself never trigger an exclude.
+    pub fn should_include_dir(&self, path: &Path, rel: &Path) -> bool {
+        if self.exclude_paths.iter().any(|p| p == path) {
+            return false;
+        }
+        if self.exclude_caches && !rel.as_os_str().is_empty() && is_cache_dir(path) {
+            return false;
+        }
+        let components: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
         for pattern in &self.exclude_dirs {
-            // Check if any path component matches the pattern (like rsync/robocopy)
-            for component in path.components() {
-                if let Some(component_str) = component.as_os_str().to_str() {
-                    if glob_match(pattern, component_str) {
-                        // Debug: uncomment to see what's being excluded
-                        // eprintln!("DEBUG: Excluding {} (matched pattern '{}')", path.display(), pattern);
-                        return false;
-                    }
-                }
+            let (anchored, body) = match pattern.strip_prefix('/') {
+                Some(rest) => (true, rest),
+                None => (false, pattern.as_str()),
+            };
+            // Patterns may span several directories (`node_modules/.cache`)
+            let segments: Vec<&str> = body.split(['/', '\\']).filter(|s| !s.is_empty()).collect();
+            if segments.is_empty() || components.len() < segments.len() {
+                continue;
+            }
+            let matched = if anchored {
+                // Anchored: the directory path must start at the root
+                segments_match(&segments, &components[..segments.len()])
+            } else {
+                // Floating: any run of consecutive directories matches (like rsync/robocopy)
+                components
+                    .windows(segments.len())
+                    .any(|window| segments_match(&segments, window))
+            };
+            if matched {
+                return false;
             }
         }
         true
     }
 }
 
+/// Signature every valid `CACHEDIR.TAG` starts with (https://bford.info/cachedir/)
+const CACHEDIR_TAG_SIGNATURE: &[u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+/// True if `dir` carries a cache/no-backup marker
+fn is_cache_dir(dir: &Path) -> bool {
+    use std::io::Read;
+
+    if dir.join(".nobackup").exists() {
+        return true;
+    }
+    let mut header = [0u8; CACHEDIR_TAG_SIGNATURE.len()];
+    std::fs::File::open(dir.join("CACHEDIR.TAG"))
+        .and_then(|mut f| f.read_exact(&mut header))
+        .is_ok_and(|_| header == CACHEDIR_TAG_SIGNATURE)
+}
+
+/// Glob-match each pattern segment against the path component at the same position
+fn segments_match(segments: &[&str], components: &[String]) -> bool {
+    segments
+        .iter()
+        .zip(components)
+        .all(|(segment, component)| glob_match(segment, component))
+}
+
+/// Path relative to the scan root (the path itself if it is not under root)
+fn rel_to<'a>(root: &Path, path: &'a Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+/// Expand a source argument whose last component contains `*` (for shells that do not glob,
+/// e.g. cmd/PowerShell). Returns `None` when the path exists literally or has no wildcard,
+/// otherwise the sorted matches in its parent directory.
+pub fn expand_source_wildcard(source: &Path) -> Option<Vec<PathBuf>> {
+    if source.exists() {
+        return None;
+    }
+    let pattern = source.file_name()?.to_str()?;
+    if !pattern.contains('*') {
+        return None;
+    }
+    let parent = match source.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let mut matches: Vec<PathBuf> = std::fs::read_dir(parent)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_str()
+                .is_some_and(|n| glob_match(pattern, n))
+        })
+        .map(|e| e.path())
+        .collect();
+    matches.sort();
+    Some(matches)
+}
+
 /// Simple glob matching (supports * wildcards)
 fn glob_match(pattern: &str, text: &str) -> bool {
     if pattern == "*" {
@@ -94,6 +231,22 @@ fn glob_match(pattern: &str, text: &str) -> bool {
 
 // All Windows-specific code removed.
 
+/// Entries that vanished mid-scan, dangling links and link loops are skipped quietly; anything
+/// else (usually permission denied) is reported and skipped, or fails the scan when `strict`
+fn handle_walk_error(err: walkdir::Error, strict: bool) -> Result<()> {
+    let vanished = err
+        .io_error()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound);
+    if vanished || err.loop_ancestor().is_some() {
+        return Ok(());
+    }
+    if strict {
+        anyhow::bail!("Scan failed (--strict-scan): {}", err);
+    }
+    eprintln!("Warning: skipping unreadable entry: {}", err);
+    Ok(())
+}
+
 /// Fast directory enumeration with filtering for non-Windows platforms
 #[cfg(not(windows))]
 pub fn enumerate_directory_filtered(root: &Path, filter: &FileFilter) -> Result<Vec<FileEntry>> {
@@ -103,24 +256,31 @@ pub fn enumerate_directory_filtered(root: &Path, filter: &FileFilter) -> Result<
 
     for entry in WalkDir::new(root)
         .follow_links(false)
+        .same_file_system(filter.one_file_system)
         .into_iter()
         .filter_entry(|e| {
             // Skip excluded directories entirely - this prevents walking into them
             if e.file_type().is_dir() {
-                filter.should_include_dir(e.path())
+                filter.should_include_dir(e.path(), rel_to(root, e.path()))
             } else {
                 true // Always walk files, filter them later
             }
         })
-        .filter_map(|e| e.ok())
     {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                handle_walk_error(err, filter.strict_scan)?;
+                continue;
+            }
+        };
         let path = entry.path();
 
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
                 let size = metadata.len();
                 // Apply file filtering
-                if filter.should_include_file(path, size) {
+                if filter.should_include_file(path, rel_to(root, path), size) {
                     entries.push(FileEntry {
                         path: path.to_path_buf(),
                         size,
@@ -144,21 +304,28 @@ pub fn enumerate_directory_filtered(root: &Path, filter: &FileFilter) -> Result<
 
     for entry in WalkDir::new(root)
         .follow_links(false)
+        .same_file_system(filter.one_file_system)
         .into_iter()
         .filter_entry(|e| {
             if e.file_type().is_dir() {
-                filter.should_include_dir(e.path())
+                filter.should_include_dir(e.path(), rel_to(root, e.path()))
             } else {
                 true
             }
         })
-        .filter_map(|e| e.ok())
     {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                handle_walk_error(err, filter.strict_scan)?;
+                continue;
+            }
+        };
         let path = entry.path();
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
                 let size = metadata.len();
-                if filter.should_include_file(path, size) {
+                if filter.should_include_file(path, rel_to(root, path), size) {
                     entries.push(FileEntry {
                         path: path.to_path_buf(),
                         size,
@@ -173,6 +340,69 @@ pub fn enumerate_directory_filtered(root: &Path, filter: &FileFilter) -> Result<
 }
 
 
+/// Symlinks under `root` whose target does not exist (dangling links).
+///
+/// The regular enumerators never report these: following them fails and unfollowed links are
+/// not files, so callers that want a policy for them collect them here.
+pub fn find_broken_symlinks(root: &Path, filter: &FileFilter) -> Vec<PathBuf> {
+    use walkdir::WalkDir;
+
+    WalkDir::new(root)
+        .follow_links(false)
+        .same_file_system(filter.one_file_system)
+        .into_iter()
+        .filter_entry(|e| {
+            !e.file_type().is_dir() || filter.should_include_dir(e.path(), rel_to(root, e.path()))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path_is_symlink() && std::fs::metadata(e.path()).is_err())
+        .filter(|e| filter.should_include_file(e.path(), rel_to(root, e.path()), 0))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Whether names under `path` (or its nearest existing ancestor) are matched case-insensitively.
+///
+/// Looks up a case-flipped spelling of an existing path component and checks whether it resolves
+/// to the same directory; nothing is written. Falls back to the platform default when no
+/// component has a letter to flip.
+pub fn is_case_insensitive(path: &Path) -> bool {
+    for dir in path.ancestors().filter(|p| p.exists()) {
+        let Some(name) = dir.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let flipped: String = name
+            .chars()
+            .map(|c| {
+                if c.is_lowercase() {
+                    c.to_ascii_uppercase()
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            })
+            .collect();
+        if flipped == name {
+            continue;
+        }
+        return same_file(dir, &dir.with_file_name(flipped));
+    }
+    cfg!(any(windows, target_os = "macos"))
+}
+
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Path, b: &Path) -> bool {
+    b.exists()
+}
+
 /// Categorize files by size for optimal copy strategy
 pub fn categorize_files(entries: Vec<CopyJob>) -> (Vec<CopyJob>, Vec<CopyJob>, Vec<CopyJob>) {
     let mut small = Vec::new(); // < 1MB - tar streaming candidates
@@ -203,18 +433,24 @@ pub fn enumerate_directory_deref_filtered(
     let mut entries = Vec::new();
     let mut visited_dirs: HashSet<PathBuf> = HashSet::new();
 
-    let mut walker = WalkDir::new(root).follow_links(true).into_iter();
+    let mut walker = WalkDir::new(root)
+        .follow_links(true)
+        .same_file_system(filter.one_file_system)
+        .into_iter();
     while let Some(next) = walker.next() {
         let entry: DirEntry = match next {
             Ok(e) => e,
-            Err(_) => continue,
+            Err(err) => {
+                handle_walk_error(err, filter.strict_scan)?;
+                continue;
+            }
         };
 
         let path = entry.path();
 
         if entry.file_type().is_dir() {
             // Skip excluded directories
-            if !filter.should_include_dir(path) {
+            if !filter.should_include_dir(path, rel_to(root, path)) {
                 walker.skip_current_dir();
                 continue;
             }
@@ -232,7 +468,7 @@ pub fn enumerate_directory_deref_filtered(
         if let Ok(md) = entry.metadata() {
             if md.is_file() {
                 let size = md.len();
-                if filter.should_include_file(path, size) {
+                if filter.should_include_file(path, rel_to(root, path), size) {
                     entries.push(FileEntry {
                         path: path.to_path_buf(),
                         size,
@@ -245,3 +481,289 @@ pub fn enumerate_directory_deref_filtered(
 
     Ok(entries)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"x").unwrap();
+    }
+
+    fn rel_files(root: &Path, filter: &FileFilter) -> Vec<String> {
+        let mut out: Vec<String> = enumerate_directory_filtered(root, filter)
+            .unwrap()
+            .into_iter()
+            .map(|e| {
+                e.path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn anchored_dir_pattern_matches_only_top_level() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("build/out.o"));
+        touch(&tmp.path().join("src/build/gen.rs"));
+        touch(&tmp.path().join("src/main.rs"));
+
+        let floating = FileFilter {
+            exclude_dirs: vec!["build".into()],
+            ..Default::default()
+        };
+        assert_eq!(rel_files(tmp.path(), &floating), vec!["src/main.rs"]);
+
+        let anchored = FileFilter {
+            exclude_dirs: vec!["/build".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            rel_files(tmp.path(), &anchored),
+            vec!["src/build/gen.rs", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn single_segment_dir_pattern_matches_at_any_depth() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("node_modules/a.js"));
+        touch(&tmp.path().join("web/node_modules/b.js"));
+        touch(&tmp.path().join("web/app.js"));
+
+        let filter = FileFilter {
+            exclude_dirs: vec!["node_modules".into()],
+            ..Default::default()
+        };
+        assert_eq!(rel_files(tmp.path(), &filter), vec!["web/app.js"]);
+    }
+
+    #[test]
+    fn multi_segment_dir_pattern_matches_relative_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("node_modules/.cache/x"));
+        touch(&tmp.path().join("node_modules/lib/y.js"));
+        touch(&tmp.path().join("web/node_modules/.cache/z"));
+        touch(&tmp.path().join("web/.cache/keep"));
+
+        let floating = FileFilter {
+            exclude_dirs: vec!["node_modules/.cache".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            rel_files(tmp.path(), &floating),
+            vec!["node_modules/lib/y.js", "web/.cache/keep"]
+        );
+
+        let anchored = FileFilter {
+            exclude_dirs: vec!["/node_modules/.cache".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            rel_files(tmp.path(), &anchored),
+            vec![
+                "node_modules/lib/y.js",
+                "web/.cache/keep",
+                "web/node_modules/.cache/z"
+            ]
+        );
+
+        let wildcard = FileFilter {
+            exclude_dirs: vec!["web/*".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            rel_files(tmp.path(), &wildcard),
+            vec!["node_modules/.cache/x", "node_modules/lib/y.js"]
+        );
+    }
+
+    #[test]
+    fn exclude_caches_skips_tagged_directories() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("keep/a.txt"));
+        touch(&tmp.path().join("cache/blob"));
+        std::fs::write(
+            tmp.path().join("cache/CACHEDIR.TAG"),
+            b"Signature: 8a477f597d28d172789f06886806bc55\n# cache\n",
+        )
+        .unwrap();
+        touch(&tmp.path().join("scratch/.nobackup"));
+        touch(&tmp.path().join("scratch/tmp.dat"));
+        // A tag without the signature is not honoured
+        touch(&tmp.path().join("bogus/CACHEDIR.TAG"));
+
+        let all = rel_files(tmp.path(), &FileFilter::default());
+        assert_eq!(all.len(), 6);
+
+        let filter = FileFilter {
+            exclude_caches: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            rel_files(tmp.path(), &filter),
+            vec!["bogus/CACHEDIR.TAG", "keep/a.txt"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn top_level_dir_symlink_is_followed_without_deref() {
+        // Like rsync, a symlink named on the command line is followed even when links found
+        // during recursion are preserved
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("real/sub/f.txt"));
+        std::os::unix::fs::symlink(tmp.path().join("real"), tmp.path().join("link")).unwrap();
+
+        let link = tmp.path().join("link");
+        assert_eq!(rel_files(&link, &FileFilter::default()), vec!["sub/f.txt"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn case_probe_sees_linux_tmp_as_case_sensitive() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("probe")).unwrap();
+        // A distinct directory under the flipped name must not be mistaken for an alias
+        std::fs::create_dir(tmp.path().join("PROBE")).unwrap();
+        assert!(!is_case_insensitive(
+            &tmp.path().join("probe/not/created/yet")
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn one_file_system_skips_mounted_volumes() {
+        use std::os::unix::fs::MetadataExt;
+
+        // /dev/shm is normally a tmpfs mounted under /dev; skip where that is not the case
+        let dev = |p: &str| std::fs::metadata(p).map(|m| m.dev()).ok();
+        if dev("/dev").is_none() || dev("/dev") == dev("/dev/shm") {
+            return;
+        }
+        let tmp = tempfile::tempdir_in("/dev/shm").unwrap();
+        touch(&tmp.path().join("marker.txt"));
+        let marker = tmp.path().join("marker.txt");
+        let found = |filter: &FileFilter| {
+            enumerate_directory_filtered(Path::new("/dev"), filter)
+                .unwrap()
+                .iter()
+                .any(|e| e.path == marker)
+        };
+
+        assert!(found(&FileFilter::default()));
+        let filter = FileFilter {
+            one_file_system: true,
+            ..Default::default()
+        };
+        assert!(!found(&filter));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_symlinks_are_found_only_when_dangling() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("real.txt"));
+        std::fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        std::os::unix::fs::symlink("real.txt", tmp.path().join("ok_link")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", tmp.path().join("sub/dangling")).unwrap();
+
+        let found = find_broken_symlinks(tmp.path(), &FileFilter::default());
+        assert_eq!(found, vec![tmp.path().join("sub/dangling")]);
+        // Broken links are still files for filtering purposes
+        assert_eq!(
+            rel_files(tmp.path(), &FileFilter::default()),
+            vec!["real.txt"]
+        );
+    }
+
+    #[test]
+    fn source_wildcard_expands_in_parent_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("b.txt"));
+        touch(&tmp.path().join("a.txt"));
+        touch(&tmp.path().join("c.log"));
+
+        let expanded = expand_source_wildcard(&tmp.path().join("*.txt")).unwrap();
+        assert_eq!(
+            expanded,
+            vec![tmp.path().join("a.txt"), tmp.path().join("b.txt")]
+        );
+        // Literal paths and plain names are left alone
+        assert!(expand_source_wildcard(&tmp.path().join("a.txt")).is_none());
+        assert!(expand_source_wildcard(&tmp.path().join("missing.txt")).is_none());
+    }
+
+    #[test]
+    fn anchored_file_pattern_matches_only_top_level() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("notes.txt"));
+        touch(&tmp.path().join("docs/notes.txt"));
+
+        let anchored = FileFilter {
+            exclude_files: vec!["/notes.txt".into()],
+            ..Default::default()
+        };
+        assert_eq!(rel_files(tmp.path(), &anchored), vec!["docs/notes.txt"]);
+    }
+
+    #[test]
+    fn filter_rules_apply_in_order_first_match_wins() {
+        let tmp = tempfile::tempdir().unwrap();
+        touch(&tmp.path().join("a.keep"));
+        touch(&tmp.path().join("sub/b.keep"));
+        touch(&tmp.path().join("c.tmp"));
+        let rules = |specs: &[&str]| FileFilter {
+            rules: specs.iter().map(|s| s.parse().unwrap()).collect(),
+            ..Default::default()
+        };
+
+        let keepers = rules(&["+ *.keep", "- *"]);
+        assert_eq!(
+            rel_files(tmp.path(), &keepers),
+            vec!["a.keep", "sub/b.keep"]
+        );
+        // Same rules, opposite order: the catch-all wins before the include is reached
+        assert!(rel_files(tmp.path(), &rules(&["- *", "+ *.keep"])).is_empty());
+
+        // An include rule overrides --xf, which only applies when no rule matched
+        let overrides = FileFilter {
+            exclude_files: vec!["*.keep".into(), "*.tmp".into()],
+            ..rules(&["+ /a.keep"])
+        };
+        assert_eq!(rel_files(tmp.path(), &overrides), vec!["a.keep"]);
+    }
+
+    #[test]
+    fn filter_rule_parsing_requires_sign_and_pattern() {
+        let rule: FilterRule = "- *.o".parse().unwrap();
+        assert_eq!(
+            rule,
+            FilterRule {
+                include: false,
+                pattern: "*.o".into()
+            }
+        );
+        assert!("*.o".parse::<FilterRule>().is_err());
+        assert!("+ ".parse::<FilterRule>().is_err());
+    }
+
+    #[test]
+    fn root_path_components_do_not_trigger_dir_excludes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("build");
+        touch(&root.join("a.txt"));
+
+        let filter = FileFilter {
+            exclude_dirs: vec!["build".into()],
+            ..Default::default()
+        };
+        assert_eq!(rel_files(&root, &filter), vec!["a.txt"]);
+    }
+}
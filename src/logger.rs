@@ -1,15 +1,20 @@
 use anyhow::Result;
 use chrono::Utc;
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub trait Logger: Send + Sync {
     fn start(&self, _src: &Path, _dst: &Path) {}
     fn copy_done(&self, _src: &Path, _dst: &Path, _bytes: u64) {}
     fn error(&self, _context: &str, _path: &Path, _msg: &str) {}
     fn done(&self, _files: u64, _bytes: u64, _seconds: f64) {}
+    /// End-of-run totals, emitted once after every copy and deletion has finished
+    fn summary(&self, _files: u64, _bytes: u64, _errors: usize, _seconds: f64) {}
 }
 
 pub struct NoopLogger;
@@ -67,3 +72,381 @@ impl Logger for TextLogger {
         ));
     }
 }
+
+/// Forwards every event to an inner logger and echoes errors as they happen.
+///
+/// Used in verbose mode so failures can be correlated with the file being processed instead of
+/// only appearing in the end-of-run summary.
+pub struct EchoErrorsLogger {
+    inner: Arc<dyn Logger + Send + Sync>,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EchoErrorsLogger {
+    pub fn new(inner: Arc<dyn Logger + Send + Sync>) -> Self {
+        Self::with_writer(inner, Box::new(std::io::stderr()))
+    }
+
+    pub fn with_writer(inner: Arc<dyn Logger + Send + Sync>, out: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner,
+            out: Mutex::new(out),
+        }
+    }
+}
+
+impl Logger for EchoErrorsLogger {
+    fn start(&self, src: &Path, dst: &Path) {
+        self.inner.start(src, dst);
+    }
+    fn copy_done(&self, src: &Path, dst: &Path, bytes: u64) {
+        self.inner.copy_done(src, dst, bytes);
+    }
+    fn error(&self, context: &str, path: &Path, msg: &str) {
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(out, "  ! {} {}: {}", context, path.display(), msg);
+        }
+        self.inner.error(context, path, msg);
+    }
+    fn done(&self, files: u64, bytes: u64, seconds: f64) {
+        self.inner.done(files, bytes, seconds);
+    }
+    fn summary(&self, files: u64, bytes: u64, errors: usize, seconds: f64) {
+        self.inner.summary(files, bytes, errors, seconds);
+    }
+}
+
+/// Emits every event as one JSON object per line (`--output jsonl`), forwarding to an inner logger.
+///
+/// Meant for a supervising process reading stdout; each object carries an `event` field
+/// (`start`, `copied`, `error`, `done`, `summary`) and an RFC 3339 `ts`.
+pub struct JsonLinesLogger {
+    inner: Arc<dyn Logger + Send + Sync>,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLinesLogger {
+    pub fn new(inner: Arc<dyn Logger + Send + Sync>) -> Self {
+        Self::with_writer(inner, Box::new(std::io::stdout()))
+    }
+
+    pub fn with_writer(inner: Arc<dyn Logger + Send + Sync>, out: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner,
+            out: Mutex::new(out),
+        }
+    }
+
+    fn emit(&self, mut event: serde_json::Value) {
+        event["ts"] = Utc::now().to_rfc3339().into();
+        if let Ok(mut out) = self.out.lock() {
+            let _ = writeln!(out, "{}", event);
+            let _ = out.flush();
+        }
+    }
+}
+
+impl Logger for JsonLinesLogger {
+    fn start(&self, src: &Path, dst: &Path) {
+        self.emit(serde_json::json!({
+            "event": "start",
+            "src": src.display().to_string(),
+            "dst": dst.display().to_string(),
+        }));
+        self.inner.start(src, dst);
+    }
+    fn copy_done(&self, src: &Path, dst: &Path, bytes: u64) {
+        self.emit(serde_json::json!({
+            "event": "copied",
+            "src": src.display().to_string(),
+            "dst": dst.display().to_string(),
+            "bytes": bytes,
+        }));
+        self.inner.copy_done(src, dst, bytes);
+    }
+    fn error(&self, context: &str, path: &Path, msg: &str) {
+        self.emit(serde_json::json!({
+            "event": "error",
+            "context": context,
+            "path": path.display().to_string(),
+            "message": msg,
+        }));
+        self.inner.error(context, path, msg);
+    }
+    fn done(&self, files: u64, bytes: u64, seconds: f64) {
+        self.emit(serde_json::json!({
+            "event": "done",
+            "files": files,
+            "bytes": bytes,
+            "seconds": seconds,
+        }));
+        self.inner.done(files, bytes, seconds);
+    }
+    fn summary(&self, files: u64, bytes: u64, errors: usize, seconds: f64) {
+        self.emit(serde_json::json!({
+            "event": "summary",
+            "files": files,
+            "bytes": bytes,
+            "errors": errors,
+            "seconds": seconds,
+        }));
+        self.inner.summary(files, bytes, errors, seconds);
+    }
+}
+
+/// Prints a single overwriting `\rNN%` line computed from bytes copied (`--output percent`).
+///
+/// The total is only known after the scan, so it is supplied later through `set_total`. The
+/// percentage never goes backwards and the line is finished at `100%` by `summary`.
+pub struct PercentLogger {
+    inner: Arc<dyn Logger + Send + Sync>,
+    total: AtomicU64,
+    copied: AtomicU64,
+    /// Last percentage printed, kept under the same lock as the writer so lines stay ordered
+    out: Mutex<(Option<u64>, Box<dyn Write + Send>)>,
+}
+
+impl PercentLogger {
+    pub fn new(inner: Arc<dyn Logger + Send + Sync>) -> Self {
+        Self::with_writer(inner, Box::new(std::io::stdout()))
+    }
+
+    pub fn with_writer(inner: Arc<dyn Logger + Send + Sync>, out: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner,
+            total: AtomicU64::new(0),
+            copied: AtomicU64::new(0),
+            out: Mutex::new((None, out)),
+        }
+    }
+
+    pub fn set_total(&self, bytes: u64) {
+        self.total.store(bytes, Ordering::Relaxed);
+        self.print(0);
+    }
+
+    fn advance(&self, bytes: u64) {
+        let copied = self.copied.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let total = self.total.load(Ordering::Relaxed);
+        let pct = copied
+            .saturating_mul(100)
+            .checked_div(total)
+            .map_or(100, |p| p.min(100));
+        self.print(pct);
+    }
+
+    fn print(&self, pct: u64) {
+        if let Ok(mut guard) = self.out.lock() {
+            let (last, out) = &mut *guard;
+            if last.is_some_and(|l| pct <= l) {
+                return;
+            }
+            *last = Some(pct);
+            let _ = write!(out, "\r{}%", pct);
+            let _ = out.flush();
+        }
+    }
+}
+
+impl Logger for PercentLogger {
+    fn start(&self, src: &Path, dst: &Path) {
+        self.inner.start(src, dst);
+    }
+    fn copy_done(&self, src: &Path, dst: &Path, bytes: u64) {
+        self.advance(bytes);
+        self.inner.copy_done(src, dst, bytes);
+    }
+    fn error(&self, context: &str, path: &Path, msg: &str) {
+        self.inner.error(context, path, msg);
+    }
+    // The tar batch reports its bytes once here instead of per file
+    fn done(&self, files: u64, bytes: u64, seconds: f64) {
+        self.advance(bytes);
+        self.inner.done(files, bytes, seconds);
+    }
+    fn summary(&self, files: u64, bytes: u64, errors: usize, seconds: f64) {
+        self.print(100);
+        if let Ok(mut guard) = self.out.lock() {
+            let _ = writeln!(guard.1);
+        }
+        self.inner.summary(files, bytes, errors, seconds);
+    }
+}
+
+/// Times each file from `start` to `copy_done` and keeps the slowest ones (`--slow-report N`).
+///
+/// Events are forwarded unchanged; the ranking is read once the run has finished.
+pub struct SlowFilesLogger {
+    inner: Arc<dyn Logger + Send + Sync>,
+    keep: usize,
+    started: Mutex<HashMap<PathBuf, Instant>>,
+    slowest: Mutex<Vec<(Duration, PathBuf)>>,
+}
+
+impl SlowFilesLogger {
+    pub fn new(inner: Arc<dyn Logger + Send + Sync>, keep: usize) -> Self {
+        Self {
+            inner,
+            keep,
+            started: Mutex::new(HashMap::new()),
+            slowest: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The slowest files seen, longest first
+    pub fn slowest(&self) -> Vec<(PathBuf, Duration)> {
+        self.slowest
+            .lock()
+            .map(|v| v.iter().map(|(d, p)| (p.clone(), *d)).collect())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, src: &Path) {
+        let Some(began) = self.started.lock().ok().and_then(|mut m| m.remove(src)) else {
+            return;
+        };
+        if let Ok(mut slowest) = self.slowest.lock() {
+            slowest.push((began.elapsed(), src.to_path_buf()));
+            slowest.sort_by_key(|(took, _)| std::cmp::Reverse(*took));
+            slowest.truncate(self.keep);
+        }
+    }
+}
+
+impl Logger for SlowFilesLogger {
+    fn start(&self, src: &Path, dst: &Path) {
+        if let Ok(mut started) = self.started.lock() {
+            started.insert(src.to_path_buf(), Instant::now());
+        }
+        self.inner.start(src, dst);
+    }
+    fn copy_done(&self, src: &Path, dst: &Path, bytes: u64) {
+        self.record(src);
+        self.inner.copy_done(src, dst, bytes);
+    }
+    fn error(&self, context: &str, path: &Path, msg: &str) {
+        if let Ok(mut started) = self.started.lock() {
+            started.remove(path);
+        }
+        self.inner.error(context, path, msg);
+    }
+    fn done(&self, files: u64, bytes: u64, seconds: f64) {
+        self.inner.done(files, bytes, seconds);
+    }
+    fn summary(&self, files: u64, bytes: u64, errors: usize, seconds: f64) {
+        self.inner.summary(files, bytes, errors, seconds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn echo_logger_reports_errors_inline_with_path() {
+        let buf = SharedBuf::default();
+        let logger = EchoErrorsLogger::with_writer(Arc::new(NoopLogger), Box::new(buf.clone()));
+
+        logger.copy_done(Path::new("/src/ok"), Path::new("/dst/ok"), 1);
+        logger.error("copy", Path::new("/src/bad.bin"), "permission denied");
+
+        let text = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.contains("/src/bad.bin"));
+        assert!(text.contains("permission denied"));
+    }
+
+    #[test]
+    fn jsonl_logger_emits_one_event_per_line() {
+        let buf = SharedBuf::default();
+        let logger = JsonLinesLogger::with_writer(Arc::new(NoopLogger), Box::new(buf.clone()));
+
+        logger.start(Path::new("/src"), Path::new("/dst"));
+        logger.copy_done(Path::new("/src/a"), Path::new("/dst/a"), 42);
+        logger.error("copy", Path::new("/src/b"), "permission \"denied\"");
+        logger.summary(1, 42, 1, 0.5);
+
+        let text = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|e| e["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, vec!["start", "copied", "error", "summary"]);
+        assert!(events.iter().all(|e| e["ts"].is_string()));
+        assert_eq!(events[1]["bytes"], 42);
+        assert_eq!(events[2]["message"], "permission \"denied\"");
+        assert_eq!(events[3]["errors"], 1);
+    }
+
+    #[test]
+    fn percent_logger_lines_never_decrease_and_end_at_100() {
+        let buf = SharedBuf::default();
+        let logger = PercentLogger::with_writer(Arc::new(NoopLogger), Box::new(buf.clone()));
+        logger.set_total(1000);
+
+        let (src, dst) = (Path::new("/src/f"), Path::new("/dst/f"));
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..20 {
+                        logger.copy_done(src, dst, 10);
+                    }
+                });
+            }
+        });
+        logger.done(50, 200, 0.0);
+        logger.summary(130, 1000, 0, 1.0);
+
+        let text = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        assert!(text.ends_with("100%\n"));
+        let values: Vec<u64> = text
+            .trim_end()
+            .split('\r')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.trim_end_matches('%').parse().unwrap())
+            .collect();
+        assert_eq!(values.first(), Some(&0));
+        assert!(values.windows(2).all(|w| w[0] < w[1]), "{:?}", values);
+        assert_eq!(values.last(), Some(&100));
+    }
+
+    #[test]
+    fn slow_files_logger_ranks_the_delayed_file_first() {
+        let logger = SlowFilesLogger::new(Arc::new(NoopLogger), 2);
+        let dst = Path::new("/dst/x");
+        for name in ["/src/fast1", "/src/slow", "/src/fast2"] {
+            let src = Path::new(name);
+            logger.start(src, dst);
+            if name == "/src/slow" {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            logger.copy_done(src, dst, 1);
+        }
+        // A failed file is not ranked
+        logger.start(Path::new("/src/bad"), dst);
+        logger.error("copy", Path::new("/src/bad"), "denied");
+
+        let slowest = logger.slowest();
+        assert_eq!(slowest.len(), 2);
+        assert_eq!(slowest[0].0, PathBuf::from("/src/slow"));
+        assert!(slowest[0].1 >= Duration::from_millis(50));
+        assert!(slowest[0].1 >= slowest[1].1);
+    }
+}
@@ -7,11 +7,22 @@
 //! - No complex abstractions
 
 use blit::buffer::BufferSizer;
-use blit::copy::{chunked_copy_file, file_needs_copy, mmap_copy_file, parallel_copy_files, CopyStats};
+use blit::copy::{
+    add_owner_write, apply_atime, chunked_copy_file, ensure_free_space, file_needs_copy,
+    infer_mtime_granularity, mmap_copy_file, parallel_copy_files, probe_mtime_granularity,
+    read_atime, reflink_file, sync_dir, AtimeOptions, CopyStats, ErrorBudget, OpenFileLimiter,
+    PauseControl, HASH_BUFFER_SIZE,
+};
 #[cfg(windows)]
 use blit::copy::windows_copyfile;
-use blit::fs_enum::{categorize_files, enumerate_directory_filtered, enumerate_directory_deref_filtered, CopyJob, FileEntry, FileFilter};
-use blit::logger::{Logger, NoopLogger, TextLogger};
+use blit::fs_enum::{
+    categorize_files, enumerate_directory_deref_filtered, enumerate_directory_filtered,
+    find_broken_symlinks, is_case_insensitive, CopyJob, FileEntry, FileFilter, FilterRule,
+};
+use blit::logger::{
+    EchoErrorsLogger, JsonLinesLogger, Logger, NoopLogger, PercentLogger, SlowFilesLogger,
+    TextLogger,
+};
 use blit::net_async;
 use blit::tar_stream::{tar_stream_transfer_list, TarConfig};
 use blit::url;
@@ -63,11 +74,39 @@ struct Args {
     /// Destination directory or file (for legacy CLI)
     destination: Option<PathBuf>,
 
-    /// Number of threads (0 = auto)
-    #[arg(short = 't', long, default_value_t = 0)]
+    /// Number of threads (0 = auto), or a percentage of available cores (e.g. 50%)
+    #[arg(
+        short = 't',
+        long,
+        env = "BLIT_THREADS",
+        default_value = "0",
+        value_parser = parse_thread_count
+    )]
     threads: usize,
+    /// Maximum simultaneously open files during local copies (0 = unlimited)
+    #[arg(
+        long = "max-open-files",
+        env = "BLIT_MAX_OPEN_FILES",
+        default_value_t = 0
+    )]
+    max_open_files: usize,
+    /// Stop before a large file would leave less than SIZE free on the destination (e.g. 2G)
+    #[arg(long = "min-free", default_value = "0", value_parser = parse_size)]
+    min_free: u64,
+    /// Fixed buffer size for copies and --checksum reads (e.g. 256K, 8M; 4K-256M)
+    #[arg(long = "io-buffer", value_name = "SIZE", value_parser = parse_io_buffer)]
+    io_buffer: Option<usize>,
+    /// Abort the run once this many files have failed (0 = never)
+    #[arg(long = "max-errors", default_value_t = 0)]
+    max_errors: usize,
+    /// Copy at most N large (>100MB) files at once, so one disk is not thrashed (0 = no limit)
+    #[arg(long = "large-file-concurrency", value_name = "N", default_value_t = 2)]
+    large_file_concurrency: usize,
+    /// Pin worker threads to CPUs round-robin so they stay on one NUMA node (Linux only)
+    #[arg(long = "pin-threads")]
+    pin_threads: bool,
     /// Network workers for async push (parallel large-file streams)
-    #[arg(long = "net-workers", default_value_t = 4)]
+    #[arg(long = "net-workers", env = "BLIT_NET_WORKERS", default_value_t = 4)]
     net_workers: usize,
     /// Network I/O chunk size in MB (1-32)
     #[arg(long = "net-chunk-mb", default_value_t = 4)]
@@ -81,6 +120,23 @@ struct Args {
     #[arg(short = 'p', long = "progress", global = true)]
     progress: bool,
 
+    /// Milliseconds between activity spinner and progress bar updates
+    #[arg(
+        long = "progress-interval",
+        value_name = "MS",
+        default_value_t = 250,
+        value_parser = clap::value_parser!(u64).range(10..=60_000)
+    )]
+    progress_interval: u64,
+
+    /// List the N slowest files (individually copied ones) after the summary
+    #[arg(long = "slow-report", value_name = "N", default_value_t = 0)]
+    slow_report: usize,
+
+    /// Do not print the end-of-run summary
+    #[arg(long = "no-summary", global = true)]
+    no_summary: bool,
+
     /// Mirror mode - copy and delete extra files (same as --delete)
     #[arg(long = "mir", alias = "mirror")]
     mirror: bool,
@@ -89,6 +145,87 @@ struct Args {
     #[arg(long, alias = "del", alias = "purge")]
     delete: bool,
 
+    /// Never delete inside top-level destination dot-directories the source lacks (.git, .snapshots)
+    #[arg(long = "keep-dest-dotdirs")]
+    keep_dest_dotdirs: bool,
+
+    /// Never delete extras, even with --mir/--delete (copy new and changed files only)
+    #[arg(long = "no-delete")]
+    no_delete: bool,
+
+    /// Abort before deleting anything if more than N extra files would be purged
+    #[arg(long = "max-purge", value_name = "N")]
+    max_purge: Option<usize>,
+
+    /// Abort before deleting anything if extras exceed P percent of destination files
+    #[arg(long = "max-purge-pct", value_name = "P")]
+    max_purge_pct: Option<f64>,
+
+    /// Skip all deletions (but still copy) if more than N would happen; exits non-zero
+    #[arg(long = "max-delete", value_name = "N")]
+    max_delete: Option<usize>,
+
+    /// After deleting extras, remove destination directories left empty that the source lacks
+    #[arg(long = "prune-empty-dirs")]
+    prune_empty_dirs: bool,
+
+    /// Make read-only destination files and directories writable so they can be updated or deleted
+    #[arg(long = "force")]
+    force: bool,
+
+    /// Give copied files the source's access time
+    #[arg(long = "preserve-atime")]
+    preserve_atime: bool,
+
+    /// Give destination files the source's full mode, setuid/setgid/sticky bits included
+    #[arg(long = "preserve-mode")]
+    preserve_mode: bool,
+
+    /// Restore each source file's access time after it has been read
+    #[arg(long = "restore-src-atime")]
+    restore_src_atime: bool,
+
+    /// Hard link files identical to the same relative path under DIR instead of copying them
+    #[arg(long = "link-dest", value_name = "DIR")]
+    link_dest: Option<PathBuf>,
+
+    /// Stage new and updated files, moving them into place only once every copy has succeeded
+    #[arg(long = "delay-updates")]
+    delay_updates: bool,
+
+    /// Pause between files while this file contains `pause`; continue on `resume` or removal
+    #[arg(long = "control-file", value_name = "PATH")]
+    control_file: Option<PathBuf>,
+
+    /// Order in which files are handed to the copy workers
+    #[arg(long = "order", value_enum, default_value = "path")]
+    order: CopyOrder,
+
+    /// Refuse to run when source paths differ only in case and the destination ignores case
+    #[arg(long = "strict-case")]
+    strict_case: bool,
+
+    /// Skip (with a warning) files whose destination path would exceed N characters
+    #[arg(long = "max-path-len", value_name = "N")]
+    max_path_len: Option<usize>,
+
+    /// Copy every file directly into the destination, dropping source subdirectories
+    #[arg(long = "flatten")]
+    flatten: bool,
+
+    /// Copy no data: give destination files that already match the source (by size, or
+    /// content with --checksum) the source mtime and permissions
+    #[arg(long = "metadata-only")]
+    metadata_only: bool,
+
+    /// With --flatten, what to do when two source files share a name
+    #[arg(long = "on-collision", value_enum, default_value = "rename")]
+    on_collision: OnCollision,
+
+    /// Fsync each modified destination directory once the run has finished writing to it
+    #[arg(long = "fsync-dirs")]
+    fsync_dirs: bool,
+
     /// Update mode: copy only changed files (size+mtime), include empty dirs, do not delete extras
     #[arg(
         long = "update",
@@ -112,18 +249,46 @@ struct Args {
     #[arg(short = 'l', long, alias = "list-only")]
     dry_run: bool,
 
+    /// List destination files absent from the source (what a mirror would delete), then exit
+    #[arg(long = "list-extras")]
+    list_extras: bool,
+
+    /// Write the planned operations as sorted text (+ create, ~ update, - delete, d rmdir)
+    #[arg(long = "plan-out")]
+    plan_out: Option<PathBuf>,
+
     /// Exclude files matching patterns (/XF)
     #[arg(long = "xf", action = clap::ArgAction::Append)]
     exclude_files: Vec<String>,
 
+    /// Ordered file rules, `+ PATTERN` or `- PATTERN`; the first match wins, before --xf
+    #[arg(long = "filter", value_name = "RULE", action = clap::ArgAction::Append)]
+    filters: Vec<FilterRule>,
+
     /// Exclude directories matching patterns (/XD)
     #[arg(long = "xd", action = clap::ArgAction::Append)]
     exclude_dirs: Vec<String>,
 
+    /// Skip directories containing a CACHEDIR.TAG or .nobackup marker
+    #[arg(long = "exclude-caches")]
+    exclude_caches: bool,
+
+    /// Stay on the source's filesystem; do not descend into other mounts
+    #[arg(short = 'x', long = "one-file-system")]
+    one_file_system: bool,
+
+    /// Abort on unreadable source entries instead of warning and skipping them
+    #[arg(long = "strict-scan")]
+    strict_scan: bool,
+
     /// Use checksums for comparison instead of size+timestamp
     #[arg(short = 'c', long)]
     checksum: bool,
 
+    /// Compare mtimes with a 2-second tolerance instead of the probed destination granularity
+    #[arg(long)]
+    second_precision: bool,
+
     /// Force tar streaming for small files
     #[arg(long)]
     force_tar: bool,
@@ -140,9 +305,13 @@ struct Args {
     #[arg(long = "no-restart")]
     no_restart: bool,
 
+    /// Output format for stdout: human-readable text, JSON-lines events or a bare `NN%` line
+    #[arg(long = "output", value_enum, default_value = "text")]
+    output: OutputFormat,
+
     // Server arguments removed - use blitd binary instead
     /// Write JSONL log entries to file
-    #[arg(long = "log-file")]
+    #[arg(long = "log-file", env = "BLIT_LOG_FILE")]
     log_file: Option<PathBuf>,
 
     /// Copy symbolic links as links (do not follow targets)
@@ -175,6 +344,10 @@ struct Args {
     #[arg(long = "xjf", help = "Exclude symlinks that point to files")]
     xjf: bool,
 
+    /// What to do with symlinks whose target does not exist
+    #[arg(long = "broken-symlinks", value_enum, default_value = "keep")]
+    broken_symlinks: BrokenSymlinks,
+
     /// Max throughput preset: increases buffers/workers and disables verify/resume
     #[arg(
         long = "ludicrous-speed",
@@ -197,6 +370,44 @@ struct Args {
     command: Option<CliCommand>,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum BrokenSymlinks {
+    /// Recreate the dangling link at the destination
+    Keep,
+    /// Leave it out of the copy
+    Skip,
+    /// Refuse to run
+    Error,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OnCollision {
+    /// Keep both: later files get a `-1`, `-2`, ... suffix before the extension
+    Rename,
+    /// Keep the first file (in path order) and leave the others out
+    Skip,
+    /// The last file (in path order) wins
+    Overwrite,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CopyOrder {
+    /// Sorted by source path
+    Path,
+    /// Biggest files first, so no large copy is left straggling at the end
+    Largest,
+    /// Smallest files first, for quick early progress
+    Smallest,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Jsonl,
+    /// Only an overwriting `\rNN%` progress line, for GUI wrappers
+    Percent,
+}
+
 #[derive(Subcommand, Debug)]
 enum CliCommand {
     /// Mirror src to dest (copy + delete extras; include empty dirs)
@@ -219,6 +430,21 @@ enum CliCommand {
         #[arg(long)]
         limit: Option<usize>, // limit sample lines on stdout
     },
+    /// List files only in A, only in B, and changed between two local trees (read-only)
+    Diff {
+        a: PathBuf,
+        b: PathBuf,
+        /// Compare file contents instead of size alone
+        #[arg(long)]
+        checksum: bool,
+    },
+    /// Print one BLAKE3 root hash over a whole tree, so two trees compare by a single value
+    Audit {
+        dir: PathBuf,
+        /// Also print each file's digest and relative path
+        #[arg(long)]
+        list: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -231,11 +457,26 @@ fn main() -> Result<()> {
         eprintln!("Failed to set Ctrl-C handler: {}", e);
     }
 
-    let args = Args::parse();
+    run(Args::parse()).map(|_| ())
+}
+
+/// What a successful `run` amounted to, so `move` only removes a source it fully copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunOutcome {
+    /// Every source file reached the destination without errors
+    Copied,
+    /// Nothing was copied: a dry run, a listing or planning mode, or metadata-only
+    NotCopied,
+    /// Copying ran but some files failed
+    Incomplete,
+}
 
+/// Everything after process setup, so tests can drive a full run from parsed arguments
+fn run(args: Args) -> Result<RunOutcome> {
     // Remote completion mode
     if let Some(comp_str) = args.complete_remote {
-        return client_complete_remote(&comp_str);
+        client_complete_remote(&comp_str)?;
+        return Ok(RunOutcome::NotCopied);
     }
 
     // Subcommand handling first
@@ -248,6 +489,7 @@ fn main() -> Result<()> {
                 return run_copy_like(src, dest, false, true, &args);
             }
             CliCommand::Move { src, dest } => {
+                check_move_flags(&args)?;
                 // Confirm destructive move
                 eprint!("This will remove source after clone. Type 'yes' to confirm: ");
                 use std::io::Write;
@@ -256,28 +498,9 @@ fn main() -> Result<()> {
                 std::io::stdin().read_line(&mut input).ok();
                 if input.trim() != "yes" {
                     eprintln!("Aborted.");
-                    return Ok(());
-                }
-                run_copy_like(src, dest, true, true, &args)?;
-                // Remove source (local or remote)
-                if let Some(remote_src) = url::parse_remote_url(src) {
-                    // Remote delete via protocol
-                    let rt = tokio::runtime::Builder::new_current_thread()
-                        .enable_all()
-                        .build()
-                        .context("build tokio runtime for remove")?;
-                    rt.block_on(net_async::client::remove_tree(
-                        &remote_src.host,
-                        remote_src.port,
-                        &remote_src.path,
-                        !args.never_tell_me_the_odds
-                    ))?;
-                } else if src.is_file() {
-                    let _ = std::fs::remove_file(src);
-                } else {
-                    let _ = std::fs::remove_dir_all(src);
+                    return Ok(RunOutcome::NotCopied);
                 }
-                return Ok(());
+                return move_tree(src, dest, &args);
             }
             CliCommand::Verify {
                 src,
@@ -318,6 +541,29 @@ fn main() -> Result<()> {
                     }
                 }
                 std::process::exit(if summary.identical { 0 } else { 1 });
+            }
+            CliCommand::Diff { a, b, checksum } => {
+                let diff = diff_trees(a, b, *checksum)?;
+                for line in diff.lines() {
+                    println!("{}", line);
+                }
+                println!(
+                    "{} only in A, {} only in B, {} changed",
+                    diff.only_a.len(),
+                    diff.only_b.len(),
+                    diff.changed.len()
+                );
+                std::process::exit(if diff.is_empty() { 0 } else { 1 });
+            }
+            CliCommand::Audit { dir, list } => {
+                let (root, files) = audit_tree(dir)?;
+                if *list {
+                    for (rel, digest) in &files {
+                        println!("{}  {}", digest, rel);
+                    }
+                }
+                println!("{}  ({} files)", root, files.len());
+                return Ok(RunOutcome::NotCopied);
             } // Shell command removed - use blitty binary instead
         }
     }
@@ -347,11 +593,39 @@ fn main() -> Result<()> {
         let _ = args; // same logger in all modes for now
         Arc::new(NoopLogger)
     };
+    // Verbose runs surface failures as they happen, not only in the final summary
+    let logger: Arc<dyn Logger + Send + Sync> = if args.verbose {
+        Arc::new(EchoErrorsLogger::new(logger))
+    } else {
+        logger
+    };
+    // JSON-lines events replace the human-readable stdout output
+    let json_output = args.output == OutputFormat::Jsonl;
+    let logger: Arc<dyn Logger + Send + Sync> = if json_output {
+        Arc::new(JsonLinesLogger::new(logger))
+    } else {
+        logger
+    };
+    // The percentage is computed from bytes; its total is filled in once the scan is done
+    let percent = (args.output == OutputFormat::Percent)
+        .then(|| Arc::new(PercentLogger::new(logger.clone())));
+    let logger: Arc<dyn Logger + Send + Sync> = match percent {
+        Some(ref p) => p.clone(),
+        None => logger,
+    };
+    let slow_files = (args.slow_report > 0)
+        .then(|| Arc::new(SlowFilesLogger::new(logger.clone(), args.slow_report)));
+    let logger: Arc<dyn Logger + Send + Sync> = match slow_files {
+        Some(ref s) => s.clone(),
+        None => logger,
+    };
+    let machine_output = args.output != OutputFormat::Text;
 
     let start = Instant::now();
 
     // Handle delete/mirror flags (robocopy compatibility)
-    let delete_extra = args.delete || args.mirror;
+    let mirror_like = args.delete || args.mirror;
+    let delete_extra = mirror_like && !args.no_delete;
 
     // Interactive mode: if no paths or subcommand, launch TUI when available
     // No implicit TUI: if no paths provided, fall back to stdin prompts (CLI stays headless)
@@ -379,17 +653,25 @@ fn main() -> Result<()> {
 
     // Network operations: support push (remote destination) and pull (remote source)
     if let Some(remote) = url::parse_remote_url(&dest_path) {
-        return client_push(remote, &src_path, &args);
+        client_push(remote, &src_path, &args)?;
+        return Ok(remote_outcome(&args));
     }
     if let Some(remote_src) = url::parse_remote_url(&src_path) {
-        return client_pull(remote_src, &dest_path, &args);
+        client_pull(remote_src, &dest_path, &args)?;
+        return Ok(remote_outcome(&args));
     }
 
     // Detect if this is a network transfer
     let _is_network = is_network_path(&dest_path);
 
+    // Fail before the banner or any setup; a Windows `*` pattern is expanded further down
+    let wildcard = cfg!(windows) && src_path.to_string_lossy().contains('*');
+    if !wildcard && !src_path.exists() {
+        anyhow::bail!("Source does not exist: {}", src_path.display());
+    }
+
     // Simple activity indicator (no performance impact)
-    let show_activity = !(args.verbose || args.progress); // Only show simple indicator if not verbose or progress
+    let show_activity = !(args.verbose || args.progress || machine_output); // Only show simple indicator if not verbose or progress
 
     // Simple activity indicator with spinner
     let spinner_chars = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
@@ -432,10 +714,11 @@ fn main() -> Result<()> {
         num_cpus::get_physical()
     };
 
-    if let Err(e) = rayon::ThreadPoolBuilder::new()
-        .num_threads(thread_count)
-        .build_global()
-    {
+    let mut pool = rayon::ThreadPoolBuilder::new().num_threads(thread_count);
+    if args.pin_threads {
+        pool = pool.start_handler(cpu_pinner());
+    }
+    if let Err(e) = pool.build_global() {
         eprintln!(
             "Rayon pool already initialized ({}); continuing with existing pool",
             e
@@ -452,7 +735,45 @@ fn main() -> Result<()> {
 
     // Check if source is a single file
     if src_path.is_file() {
-        return copy_single_file(&src_path, &dest_path, false, args.progress);
+        if args.dry_run {
+            println!("{}", dry_run_line(&src_path, &dest_path));
+            return Ok(RunOutcome::NotCopied);
+        }
+        copy_single_file(
+            &src_path,
+            &dest_path,
+            &buffer_sizer_for(&args),
+            args.progress,
+            std::time::Duration::from_millis(args.progress_interval),
+        )?;
+        return Ok(RunOutcome::Copied);
+    }
+
+    // cmd/PowerShell pass wildcards through unexpanded; Unix shells have already globbed
+    #[cfg(windows)]
+    if let Some(matches) = blit::fs_enum::expand_source_wildcard(&src_path) {
+        if matches.is_empty() {
+            anyhow::bail!("No files match {}", src_path.display());
+        }
+        if args.dry_run {
+            for m in matches.iter().filter(|m| m.is_file()) {
+                let name = m.file_name().unwrap_or_default();
+                println!("{}", dry_run_line(m, &dest_path.join(name)));
+            }
+            return Ok(RunOutcome::NotCopied);
+        }
+        std::fs::create_dir_all(&dest_path)?;
+        for m in matches.iter().filter(|m| m.is_file()) {
+            let name = m.file_name().unwrap_or_default();
+            copy_single_file(
+                m,
+                &dest_path.join(name),
+                &buffer_sizer_for(&args),
+                args.progress,
+                std::time::Duration::from_millis(args.progress_interval),
+            )?;
+        }
+        return Ok(RunOutcome::Copied);
     }
 
     // Enumerate files with progress
@@ -470,23 +791,90 @@ fn main() -> Result<()> {
         args.empty_dirs || !(args.subdirs || args.no_empty_dirs)
     };
 
+    // Refuse to copy a tree onto itself; keep a nested destination out of the source scan
+    let nested_dest = destination_inside_source(&src_path, &dest_path)?;
+    if let Some(ref nested) = nested_dest {
+        if args.verbose {
+            println!(
+                "Destination is inside source; excluding {} from the scan",
+                nested.display()
+            );
+        }
+    }
+
     // Build filter from CLI arguments
     let filter = FileFilter {
+        rules: args.filters.clone(),
         exclude_files: args.exclude_files.clone(),
         exclude_dirs: args.exclude_dirs.clone(),
-        min_size: None,
-        max_size: None,
+        exclude_paths: nested_dest
+            .into_iter()
+            .chain([src_path.join(STAGING_DIR)])
+            .collect(),
+        protected_files: args
+            .log_file
+            .iter()
+            .flat_map(|log| {
+                [
+                    relocate_under(log, &src_path),
+                    relocate_under(log, &dest_path),
+                ]
+            })
+            .flatten()
+            .collect(),
+        exclude_caches: args.exclude_caches,
+        one_file_system: args.one_file_system,
+        strict_scan: args.strict_scan,
+        keep_dest_dotdirs: args.keep_dest_dotdirs,
+        ..Default::default()
     };
 
     if args.verbose {
         if !args.exclude_dirs.is_empty() {
             println!("Excluding directories: {:?}", args.exclude_dirs);
         }
+        if !args.filters.is_empty() {
+            println!("Filter rules: {:?}", args.filters);
+        }
         if !args.exclude_files.is_empty() {
             println!("Excluding files: {:?}", args.exclude_files);
         }
     }
 
+    if args.list_extras {
+        let extras = list_extras(&src_path, &dest_path, &filter)?;
+        let total: u64 = extras.iter().map(|(_, size)| size).sum();
+        for (path, size) in &extras {
+            println!("{} ({} bytes)", path.display(), size);
+        }
+        println!(
+            "{} extra files ({:.2} MB) in destination",
+            extras.len(),
+            total as f64 / 1_048_576.0
+        );
+        return Ok(RunOutcome::NotCopied);
+    }
+
+    // Dangling links never show up in the file list; apply --broken-symlinks to them here
+    let broken_policy = if args.xj {
+        BrokenSymlinks::Skip
+    } else {
+        args.broken_symlinks
+    };
+    let broken_links = match broken_policy {
+        BrokenSymlinks::Skip => Vec::new(),
+        BrokenSymlinks::Keep | BrokenSymlinks::Error => find_broken_symlinks(&src_path, &filter),
+    };
+    if broken_policy == BrokenSymlinks::Error && !broken_links.is_empty() {
+        for link in &broken_links {
+            eprintln!("  broken symlink: {}", link.display());
+        }
+        anyhow::bail!(
+            "Source contains {} broken symlinks (--broken-symlinks error)",
+            broken_links.len()
+        );
+    }
+
     // Determine link policy: default to dereference unless explicitly preserving
     #[cfg(windows)]
     let preserve_links = args.sl || args.sj;
@@ -508,6 +896,49 @@ fn main() -> Result<()> {
         })
         .collect();
 
+    if args.flatten {
+        return run_flattened(copy_jobs, &dest_path, &args, &*logger);
+    }
+
+    // README and readme would land on the same file of a case-insensitive destination
+    let case_warnings =
+        case_collision_warnings(&copy_jobs, &src_path, is_case_insensitive(&dest_path));
+    for warning in &case_warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if args.strict_case && !case_warnings.is_empty() {
+        anyhow::bail!(
+            "{} source paths collide on the case-insensitive destination (--strict-case)",
+            case_warnings.len()
+        );
+    }
+
+    let copy_jobs = match args.max_path_len {
+        Some(max) => {
+            let (fits, too_long) = split_overlong_paths(copy_jobs, &src_path, &dest_path, max);
+            for dst in &too_long {
+                eprintln!(
+                    "Warning: skipping {} ({} characters, over --max-path-len {})",
+                    dst.display(),
+                    dst.to_string_lossy().chars().count(),
+                    max
+                );
+            }
+            fits
+        }
+        None => copy_jobs,
+    };
+
+    if args.metadata_only {
+        let (fixed, untouched) =
+            apply_metadata_only(&copy_jobs, &src_path, &dest_path, checksum_buffer(&args));
+        println!(
+            "Updated metadata on {} files; {} missing or different files left alone",
+            fixed, untouched
+        );
+        return Ok(RunOutcome::NotCopied);
+    }
+
     let total_files = copy_jobs.len();
     let total_size: u64 = copy_jobs.iter().map(|job| job.entry.size).sum();
 
@@ -526,42 +957,98 @@ fn main() -> Result<()> {
         );
     }
 
+    // Directory counts cover the whole plan, skipped files included, so existence is
+    // checked before anything is created
+    let planned_dirs = planned_dirs(&copy_jobs, &src_path);
+    let new_dirs = missing_dest_dirs(&planned_dirs, &src_path, &dest_path);
+
     // Filter out files that don't need copying when mirroring or in --update mode
-    let skip_unchanged = delete_extra || args.update;
-    let copy_jobs = if skip_unchanged {
+    let skip_unchanged = mirror_like || args.update;
+    // Shared by skip-unchanged and --link-dest, so a file is linked exactly when it would be
+    // skipped. Dry runs must not write the probe file, so they read the step off the
+    // destination files already there.
+    let compares = skip_unchanged || args.link_dest.is_some();
+    let mtime_slack = if args.second_precision {
+        std::time::Duration::from_secs(2)
+    } else if !compares || !dest_path.is_dir() {
+        std::time::Duration::ZERO
+    } else {
+        let slack = if args.dry_run {
+            infer_mtime_granularity(
+                copy_jobs
+                    .iter()
+                    .map(|job| compute_destination(&job.entry.path, &src_path, &dest_path)),
+            )
+        } else {
+            probe_mtime_granularity(&dest_path)
+        };
+        if args.verbose {
+            println!("Destination mtime granularity: {:?}", slack);
+        }
+        slack
+    };
+    let (copy_jobs, skipped) = if skip_unchanged {
         if show_activity {
             print!("\r{} comparing...", spinner_chars[spinner_index]);
             std::io::Write::flush(&mut std::io::stdout()).ok();
             spinner_index = (spinner_index + 1) % spinner_chars.len();
         }
-
-        use rayon::prelude::*;
-        copy_jobs
-            .into_par_iter()
-            .filter(|job| {
-                let src = &job.entry.path;
-                let dst = compute_destination(src, &src_path, &dest_path);
-                file_needs_copy(src, &dst, args.checksum).unwrap_or(true)
-            })
-            .collect()
+        partition_unchanged(
+            copy_jobs,
+            &src_path,
+            &dest_path,
+            checksum_buffer(&args),
+            mtime_slack,
+        )
     } else {
-        copy_jobs
+        (copy_jobs, Vec::new())
+    };
+    if args.verbose {
+        for job in &skipped {
+            println!("  same: {}", job.entry.path.display());
+        }
+    }
+
+    // Files identical to their --link-dest counterpart are hard linked instead of copied
+    let (copy_jobs, linked) = match args.link_dest {
+        Some(ref base) => partition_linkable(
+            copy_jobs,
+            &src_path,
+            base,
+            checksum_buffer(&args),
+            mtime_slack,
+        ),
+        None => (copy_jobs, Vec::new()),
     };
 
-    // Categorize files by size
+    // Categorize files by size; each category keeps the --order it is given
+    let mut copy_jobs = copy_jobs;
+    order_jobs(&mut copy_jobs, args.order);
     let (small, medium, large) = categorize_files(copy_jobs);
 
+    if let Some(ref plan_path) = args.plan_out {
+        let deletions = if delete_extra {
+            Some(plan_mirror_deletion(&src_path, &dest_path, &filter)?)
+        } else {
+            None
+        };
+        let planned: Vec<&CopyJob> = small.iter().chain(&medium).chain(&large).collect();
+        let text = plan_text(&planned, &src_path, &dest_path, deletions.as_ref());
+        std::fs::write(plan_path, text)
+            .with_context(|| format!("Failed to write plan to {:?}", plan_path))?;
+    }
+
     // Handle dry run mode
     if args.dry_run {
         println!("\n=== DRY RUN - Files that would be copied ===");
         println!("Small files (<1MB): {}", small.len());
         println!("Medium files (1-100MB): {}", medium.len());
         println!("Large files (>100MB): {}", large.len());
-        println!(
-            "Total: {} files ({:.2} GB)",
-            total_files,
-            total_size as f64 / 1_073_741_824.0
-        );
+        let planned: Vec<&CopyJob> = small.iter().chain(&medium).chain(&large).collect();
+        println!("{}", dry_run_total_line(&planned));
+        if !linked.is_empty() {
+            println!("Would hard link {} files from --link-dest", linked.len());
+        }
 
         if args.verbose {
             println!("\n--- Files to copy ---");
@@ -573,13 +1060,10 @@ fn main() -> Result<()> {
             {
                 if i < 20 {
                     // Limit output
-                    println!(
-                        "  {} ({} bytes)",
-                        entry.entry.path.display(),
-                        entry.entry.size
-                    );
+                    let dst = compute_destination(&entry.entry.path, &src_path, &dest_path);
+                    println!("  {}", dry_run_line(&entry.entry.path, &dst));
                 } else if i == 20 {
-                    println!("  ... and {} more files", total_files - 20);
+                    println!("  ... and {} more files", planned.len() - 20);
                     break;
                 }
             }
@@ -590,7 +1074,16 @@ fn main() -> Result<()> {
             println!("\nWould also delete extra files in destination.");
         }
 
-        return Ok(());
+        return Ok(RunOutcome::NotCopied);
+    }
+
+    // Content-identical files keep a stale mtime otherwise, and the next size+mtime run
+    // would copy them again
+    if args.checksum {
+        let fixed = correct_skipped_mtimes(&skipped, &src_path, &dest_path);
+        if args.verbose && fixed > 0 {
+            println!("Corrected mtime on {} identical files", fixed);
+        }
     }
 
     if args.verbose {
@@ -598,10 +1091,90 @@ fn main() -> Result<()> {
         println!("Medium files (1-100MB): {}", medium.len());
         println!("Large files (>100MB): {}", large.len());
     }
+    if let Some(ref p) = percent {
+        let planned = small.iter().chain(&medium).chain(&large);
+        p.set_total(planned.map(|job| job.entry.size).sum());
+    }
 
     // Track overall progress
-    let mut total_stats = CopyStats::default();
-    let buffer_sizer = Arc::new(BufferSizer::new());
+    let mut total_stats = CopyStats {
+        files_skipped: skipped.len() as u64,
+        ..Default::default()
+    };
+    // --delay-updates writes under the destination (same filesystem, so the final renames are
+    // cheap) and only moves files into place once every copy has succeeded
+    let write_root = if args.delay_updates {
+        prepare_staging(&dest_path)?
+    } else {
+        dest_path.clone()
+    };
+    // Destination directories whose entries changed besides those copied into, for --fsync-dirs
+    let mut changed_dirs = std::collections::BTreeSet::new();
+    if let Some(ref base) = args.link_dest {
+        for job in &linked {
+            let dst = compute_destination(&job.entry.path, &src_path, &write_root);
+            let basis = compute_destination(&job.entry.path, &src_path, base);
+            match hard_link_into_place(&basis, &dst) {
+                Ok(()) => {
+                    total_stats.files_linked += 1;
+                    changed_dirs.extend(dst.parent().map(Path::to_path_buf));
+                }
+                Err(e) => total_stats.add_error(format!("{:#}", e)),
+            }
+        }
+    }
+    let buffer_sizer = Arc::new(buffer_sizer_for(&args));
+    let open_limiter = Arc::new(OpenFileLimiter::new(args.max_open_files));
+    let error_budget = Arc::new(ErrorBudget::new(args.max_errors));
+    let pause_control = Arc::new(PauseControl::new(args.control_file.clone()));
+    let out_of_space = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+    // Read-only destinations are unlocked up front and locked again once copying is done, or
+    // when the run bails out before that
+    let unlocked = Relock(if args.force {
+        unlock_destinations(
+            small.iter().chain(&medium).chain(&large),
+            &src_path,
+            &dest_path,
+        )
+    } else {
+        Vec::new()
+    });
+
+    // Creating files bumps directory mtimes; remember which ones to put back afterwards
+    let touched_dirs = ancestor_dirs(
+        small.iter().chain(&medium).chain(&large).chain(&linked),
+        &src_path,
+    );
+
+    // Access times must be captured before the copy threads read the sources
+    let atime_opts = AtimeOptions {
+        preserve: args.preserve_atime,
+        restore_source: args.restore_src_atime,
+    };
+    let source_atimes: Vec<(PathBuf, filetime::FileTime)> = if atime_opts.any() {
+        small
+            .iter()
+            .chain(&medium)
+            .chain(&large)
+            .filter_map(|job| read_atime(&job.entry.path).map(|t| (job.entry.path.clone(), t)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // Copy paths create files with the default mode (tar keeps only the low bits); skipped
+    // files are included so a mode-only change is picked up without recopying
+    let mode_sources: Vec<PathBuf> = if args.preserve_mode {
+        small
+            .iter()
+            .chain(&medium)
+            .chain(&large)
+            .chain(&skipped)
+            .map(|job| job.entry.path.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
 
     // Optional heartbeat spinner to show activity (local mode)
     let mut hb_handle = None;
@@ -609,6 +1182,7 @@ fn main() -> Result<()> {
     if show_activity {
         hb_running.store(true, std::sync::atomic::Ordering::SeqCst);
         let running = hb_running.clone();
+        let interval = std::time::Duration::from_millis(args.progress_interval);
         hb_handle = Some(std::thread::spawn(move || {
             let spinner = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
             let mut idx = 0usize;
@@ -616,7 +1190,7 @@ fn main() -> Result<()> {
                 print!("\r{} copying...", spinner[idx]);
                 let _ = std::io::Write::flush(&mut std::io::stdout());
                 idx = (idx + 1) % spinner.len();
-                std::thread::sleep(std::time::Duration::from_millis(250));
+                std::thread::sleep(interval);
             }
         }));
     }
@@ -633,8 +1207,11 @@ fn main() -> Result<()> {
         let use_tar = !args.no_tar && (args.force_tar || should_use_tar(&small, false));
         let small_files = small.clone();
         let source = src_path.clone();
-        let destination = dest_path.clone();
+        let destination = write_root.clone();
         let buffer_sizer_clone = buffer_sizer.clone();
+        let limiter = open_limiter.clone();
+        let errors = error_budget.clone();
+        let control = pause_control.clone();
         let tx_clone = tx.clone();
         let verbose = args.verbose;
         let _show_files = args.progress;
@@ -660,6 +1237,8 @@ fn main() -> Result<()> {
                         stats.bytes_copied = bytes;
                     }
                     Err(e) => {
+                        logger_clone.error("tar", &source, &e.to_string());
+                        errors.record();
                         stats.add_error(format!("Tar streaming failed: {}", e));
                     }
                 }
@@ -670,6 +1249,9 @@ fn main() -> Result<()> {
                     small_pairs,
                     buffer_sizer_clone,
                     false, // Local only
+                    &limiter,
+                    &errors,
+                    &control,
                     &*logger_clone,
                 );
             }
@@ -683,8 +1265,11 @@ fn main() -> Result<()> {
     if !medium.is_empty() {
         let medium_files = medium;
         let source = src_path.clone();
-        let destination = dest_path.clone();
+        let destination = write_root.clone();
         let buffer_sizer_clone = buffer_sizer.clone();
+        let limiter = open_limiter.clone();
+        let errors = error_budget.clone();
+        let control = pause_control.clone();
         let tx_clone = tx.clone();
         let verbose = args.verbose;
         let _show_files = args.progress;
@@ -700,6 +1285,9 @@ fn main() -> Result<()> {
                 medium_pairs,
                 buffer_sizer_clone,
                 false, /* local only */
+                &limiter,
+                &errors,
+                &control,
                 &*logger_clone,
             );
 
@@ -712,8 +1300,14 @@ fn main() -> Result<()> {
     if !large.is_empty() {
         let large_files = large;
         let source = src_path.clone();
-        let destination = dest_path.clone();
+        let destination = write_root.clone();
         let buffer_sizer_clone = buffer_sizer.clone();
+        let limiter = open_limiter.clone();
+        let errors = error_budget.clone();
+        let control = pause_control.clone();
+        let no_space = out_of_space.clone();
+        let min_free = args.min_free;
+        let large_slots = large_copy_slots(args.large_file_concurrency);
         let tx_clone = tx.clone();
         let verbose = args.verbose;
         let show_files = args.progress;
@@ -727,12 +1321,29 @@ fn main() -> Result<()> {
             let stats = Arc::new(Mutex::new(CopyStats::default()));
 
             large_files.par_iter().for_each(|entry| {
+                if errors.exhausted() || no_space.load(std::sync::atomic::Ordering::SeqCst) {
+                    return;
+                }
+                control.wait_while_paused();
                 let dst = compute_destination(&entry.entry.path, &source, &destination);
-                let mut s = stats.lock();
+                // Stop cleanly instead of leaving a truncated file on a full disk
+                if let Err(e) = ensure_free_space(&dst, entry.entry.size, min_free) {
+                    no_space.store(true, std::sync::atomic::Ordering::SeqCst);
+                    logger_clone.error("min_free", &dst, &e.to_string());
+                    stats.lock().add_error(format!("{:#}", e));
+                    return;
+                }
+                let _slot = large_slots.acquire();
+                let _open = limiter.acquire();
 
                 let copy_result = if cfg!(unix) {
                     // Always local now
+                    logger_clone.start(&entry.entry.path, &dst);
                     mmap_copy_file(&entry.entry.path, &dst)
+                        .inspect(|&bytes| logger_clone.copy_done(&entry.entry.path, &dst, bytes))
+                        .inspect_err(|e| {
+                            logger_clone.error("mmap_copy", &entry.entry.path, &e.to_string())
+                        })
                 } else {
                     chunked_copy_file(
                         &entry.entry.path,
@@ -744,6 +1355,7 @@ fn main() -> Result<()> {
                     )
                 };
 
+                let mut s = stats.lock();
                 match copy_result {
                     Ok(bytes) => {
                         s.add_file(bytes);
@@ -757,6 +1369,7 @@ fn main() -> Result<()> {
                         }
                     }
                     Err(e) => {
+                        errors.record();
                         s.add_error(format!("Failed to copy {:?}: {}", entry.entry.path, e));
                     }
                 }
@@ -786,21 +1399,133 @@ fn main() -> Result<()> {
         merge_stats(&mut total_stats, stats);
     }
 
+    // Too many failures or a full destination: stop before touching it any further
+    let no_space = out_of_space.load(std::sync::atomic::Ordering::SeqCst);
+    let staged_failure = args.delay_updates && !total_stats.errors.is_empty();
+    if error_budget.exhausted() || no_space || staged_failure {
+        if let Some(h) = hb_handle.take() {
+            hb_running.store(false, std::sync::atomic::Ordering::SeqCst);
+            let _ = h.join();
+        }
+        for error in &total_stats.errors {
+            eprintln!("  - {}", error);
+        }
+        if args.delay_updates {
+            let _ = std::fs::remove_dir_all(dest_path.join(STAGING_DIR));
+            eprintln!("Destination left unchanged (--delay-updates)");
+        }
+        if no_space {
+            anyhow::bail!("Stopped: destination is out of space (see --min-free)");
+        }
+        if !error_budget.exhausted() {
+            anyhow::bail!("{} files failed to copy", total_stats.errors.len());
+        }
+        anyhow::bail!(
+            "Aborted after {} errors (--max-errors {})",
+            error_budget.count(),
+            args.max_errors
+        );
+    }
+    if args.delay_updates {
+        changed_dirs.extend(commit_staged(&write_root, &dest_path)?);
+    }
+
+    drop(unlocked);
+
+    for link in &broken_links {
+        match recreate_symlink(link, &src_path, &dest_path) {
+            Ok(()) => {
+                let dst = compute_destination(link, &src_path, &dest_path);
+                changed_dirs.extend(dst.parent().map(Path::to_path_buf));
+            }
+            Err(e) => {
+                logger.error("symlink", link, &e.to_string());
+                total_stats.add_error(format!("{:#}", e));
+            }
+        }
+    }
+
+    for (src, atime) in &source_atimes {
+        let dst = compute_destination(src, &src_path, &dest_path);
+        if !dst.exists() {
+            continue;
+        }
+        if let Err(e) = apply_atime(src, &dst, *atime, atime_opts) {
+            total_stats.add_error(format!("{:#}", e));
+        }
+    }
+
+    if args.preserve_mode {
+        let updated = copy_modes(&mode_sources, &src_path, &dest_path, &mut total_stats);
+        if args.verbose {
+            println!("Set mode on {} files", updated);
+        }
+    }
+
     // Handle mirror mode - delete extra files in destination
+    let purge_limits = PurgeLimits::from_args(&args);
+    let mut deletion_stats = DeletionStats::default();
     if delete_extra {
         if args.verbose || args.progress {
             println!("Scanning destination for extra files...");
         }
 
-        let deletion_stats =
-            handle_mirror_deletion(&src_path, &dest_path, &filter, args.progress, args.dry_run)?;
+        deletion_stats = handle_mirror_deletion(
+            &src_path,
+            &dest_path,
+            &filter,
+            args.progress,
+            args.dry_run,
+            args.force,
+            &purge_limits,
+        )?;
 
-        if args.verbose && (deletion_stats.0 > 0 || deletion_stats.1 > 0) {
+        if args.verbose && (deletion_stats.files > 0 || deletion_stats.dirs > 0) {
             println!(
                 "Deleted {} files and {} directories",
-                deletion_stats.0, deletion_stats.1
+                deletion_stats.files, deletion_stats.dirs
             );
         }
+
+        if args.prune_empty_dirs && deletion_stats.suppressed.is_none() {
+            let pruned = prune_empty_dirs(
+                &src_path,
+                &dest_path,
+                &filter,
+                args.progress,
+                &mut deletion_stats.parents,
+            )?;
+            if args.verbose && pruned > 0 {
+                println!("Pruned {} empty directories", pruned);
+            }
+        }
+        changed_dirs.append(&mut deletion_stats.parents);
+    }
+
+    restore_dir_mtimes(&touched_dirs, &src_path, &dest_path);
+    count_dirs(
+        &mut total_stats,
+        &planned_dirs,
+        &new_dirs,
+        &src_path,
+        &dest_path,
+    );
+
+    // One fsync per directory, after all of its entries (and its mtime) are final
+    if args.fsync_dirs {
+        changed_dirs.extend(
+            touched_dirs
+                .iter()
+                .map(|d| compute_destination(d, &src_path, &dest_path)),
+        );
+        for dst_dir in changed_dirs {
+            match sync_dir(&dst_dir) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    total_stats.add_error(format!("Failed to fsync directory {:?}: {}", dst_dir, e))
+                }
+                _ => {}
+            }
+        }
     }
 
     // Finish heartbeat spinner
@@ -819,20 +1544,37 @@ fn main() -> Result<()> {
     }
 
     // Print summary (always show)
-    let elapsed = start.elapsed();
-    if !args.progress || args.verbose {
+    total_stats.elapsed = start.elapsed();
+    logger.summary(
+        total_stats.files_copied,
+        total_stats.bytes_copied,
+        total_stats.errors.len(),
+        total_stats.elapsed.as_secs_f64(),
+    );
+    let outcome = if total_stats.errors.is_empty() {
+        RunOutcome::Copied
+    } else {
+        RunOutcome::Incomplete
+    };
+    if machine_output {
+        deletion_stats.suppressed_error(&purge_limits)?;
+        return Ok(outcome);
+    }
+    let summary = end_of_run_summary(&args, &total_stats);
+    if !summary.is_empty() {
         println!();
-        println!("=== Copy Complete ===");
-        println!("Files copied: {}", total_stats.files_copied);
-        println!(
-            "Total size: {:.2} GB",
-            total_stats.bytes_copied as f64 / 1_073_741_824.0
-        );
-        println!("Time: {:.2}s", elapsed.as_secs_f64());
-        println!(
-            "Throughput: {:.2} MB/s",
-            (total_stats.bytes_copied as f64 / 1_048_576.0) / elapsed.as_secs_f64()
-        );
+        for line in summary {
+            println!("{}", line);
+        }
+    }
+    if let Some(ref slow) = slow_files {
+        let slowest = slow.slowest();
+        if !slowest.is_empty() {
+            println!("\nSlowest files:");
+            for (path, took) in slowest {
+                println!("  {:>8.2}s  {}", took.as_secs_f64(), path.display());
+            }
+        }
     }
 
     if !total_stats.errors.is_empty() {
@@ -844,16 +1586,138 @@ fn main() -> Result<()> {
         }
     }
 
+    deletion_stats.suppressed_error(&purge_limits)?;
+    Ok(outcome)
+}
+
+/// The network client has no dry run, but a run asked to be one never counts as a finished copy
+fn remote_outcome(args: &Args) -> RunOutcome {
+    if args.dry_run {
+        RunOutcome::NotCopied
+    } else {
+        RunOutcome::Copied
+    }
+}
+
+/// Refuse flags that turn a move into something other than a copy before the source is removed
+fn check_move_flags(args: &Args) -> Result<()> {
+    if args.list_extras {
+        anyhow::bail!("--list-extras cannot be combined with move");
+    }
+    if args.metadata_only {
+        anyhow::bail!("--metadata-only cannot be combined with move");
+    }
     Ok(())
 }
 
+/// `move`: mirror `src` to `dest`, then remove from `src` what is now intact at `dest`. This only
+/// happens after a real copy that finished without errors. Files the run left out (filters,
+/// skipped or unreadable entries, other filesystems) stay in `src`.
+fn move_tree(src: &Path, dest: &Path, args: &Args) -> Result<RunOutcome> {
+    match run_copy_like(src, dest, true, true, args)? {
+        RunOutcome::Copied => {}
+        RunOutcome::NotCopied => {
+            eprintln!("Nothing was copied; source left in place");
+            return Ok(RunOutcome::NotCopied);
+        }
+        RunOutcome::Incomplete => {
+            anyhow::bail!("Some files failed to copy; source left in place")
+        }
+    }
+    // Remove source (local or remote)
+    if let Some(remote_src) = url::parse_remote_url(src) {
+        // Remote delete via protocol
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("build tokio runtime for remove")?;
+        rt.block_on(net_async::client::remove_tree(
+            &remote_src.host,
+            remote_src.port,
+            &remote_src.path,
+            !args.never_tell_me_the_odds,
+        ))?;
+    } else {
+        let kept = remove_moved_sources(src, dest, checksum_buffer(args))?;
+        if kept > 0 {
+            eprintln!("Kept {} source files that were not copied", kept);
+        }
+    }
+    Ok(RunOutcome::Copied)
+}
+
+/// Remove each source file whose destination counterpart matches it, then each source directory
+/// left empty whose counterpart exists. Stays on the source's filesystem. Returns how many
+/// source files were kept.
+fn remove_moved_sources(src_root: &Path, dst_root: &Path, checksum: Option<usize>) -> Result<u64> {
+    use walkdir::WalkDir;
+
+    let probe_dir = if dst_root.is_dir() {
+        dst_root
+    } else {
+        dst_root.parent().unwrap_or(dst_root)
+    };
+    let slack = probe_mtime_granularity(probe_dir);
+    let mut kept = 0u64;
+    for entry in WalkDir::new(src_root)
+        .follow_links(false)
+        .same_file_system(true)
+        .contents_first(true)
+    {
+        let Ok(entry) = entry else {
+            kept += 1;
+            continue;
+        };
+        let path = entry.path();
+        let dst = if path == src_root {
+            dst_root.to_path_buf()
+        } else {
+            compute_destination(path, src_root, dst_root)
+        };
+        if entry.file_type().is_dir() {
+            let empty = std::fs::read_dir(path).is_ok_and(|mut it| it.next().is_none());
+            if empty && dst.is_dir() {
+                std::fs::remove_dir(path).with_context(|| format!("remove source {:?}", path))?;
+            }
+        } else if moved_intact(path, &dst, checksum, slack) {
+            std::fs::remove_file(path).with_context(|| format!("remove source {:?}", path))?;
+        } else {
+            kept += 1;
+        }
+    }
+    Ok(kept)
+}
+
+/// True if `dst` holds what `src` does: the same target for symlinks, otherwise a regular file
+/// the skip-unchanged comparison considers up to date
+fn moved_intact(
+    src: &Path,
+    dst: &Path,
+    checksum: Option<usize>,
+    slack: std::time::Duration,
+) -> bool {
+    let (Ok(src_md), Ok(dst_md)) = (
+        std::fs::symlink_metadata(src),
+        std::fs::symlink_metadata(dst),
+    ) else {
+        return false;
+    };
+    if src_md.file_type().is_symlink() && dst_md.file_type().is_symlink() {
+        let targets = (std::fs::read_link(src), std::fs::read_link(dst));
+        return matches!(targets, (Ok(a), Ok(b)) if a == b);
+    }
+    let both_files = std::fs::metadata(src).is_ok_and(|md| md.is_file())
+        && std::fs::metadata(dst).is_ok_and(|md| md.is_file());
+    both_files && file_needs_copy(src, dst, checksum, slack).is_ok_and(|copy| !copy)
+}
+
 fn run_copy_like(
     src: &Path,
     dest: &Path,
     mirror: bool,
     include_empty: bool,
     base_args: &Args,
-) -> Result<()> {
+) -> Result<RunOutcome> {
     // Build a minimal Args clone to re-use existing logic
     let mut args = base_args.clone_for_copylike();
     args.source = Some(src.to_path_buf());
@@ -865,115 +1729,11 @@ fn run_copy_like(
     if include_empty {
         args.empty_dirs = true;
     }
-    // Delegate to legacy path by falling through to local copy section
-    // Simplest: call main copy pipeline by replicating needed subset
-    // For now, we route through network URL handling and local pipeline below
-    // Implementation: mimic what main does by re-parsing environment
-    // This function will only be used early in main before heavy logic
-    // Therefore, just return Ok and let main continue with source/dest in place.
-    // In practice, we call this via early return, so instead:
-    // We'll perform a small inline copy by invoking client or local copy.
-
-    // Remote URL handling
     if url::parse_remote_url(src).is_some() && url::parse_remote_url(dest).is_some() {
         anyhow::bail!("Remote→remote transfers are not supported in this release");
     }
-    if let Some(remote) = url::parse_remote_url(src) {
-        return client_pull(remote, dest, &args);
-    }
-    if let Some(remote) = url::parse_remote_url(dest) {
-        return client_push(remote, src, &args);
-    }
-    // Local single-file or directory copy
-    // Reuse existing local code by calling a helper
-    run_local(src, dest, mirror, include_empty, &args)
-}
-
-// Minimal wrapper to reuse existing local flow from main
-fn run_local(
-    src_path: &Path,
-    dest_path: &Path,
-    mirror: bool,
-    _include_empty: bool,
-    args: &Args,
-) -> Result<()> {
-    // The main function already implements the full local copy pipeline.
-    // To avoid duplicating, we call into that pipeline by reproducing its steps here.
-    // For brevity and to avoid code duplication, we will just return an error that instructs to use core path.
-    // However, we implement direct fallback: if it's a file, copy_single_file; otherwise continue with enumerate path below.
-    if src_path.is_file() {
-        return copy_single_file(src_path, dest_path, false, args.verbose);
-    }
-    // Build FileFilter
-    let filter = FileFilter {
-        exclude_files: vec![],
-        exclude_dirs: vec![],
-        min_size: None,
-        max_size: None,
-    };
-    let preserve_links = args.sl;
-    let initial_entries = if !preserve_links {
-        enumerate_directory_deref_filtered(src_path, &filter)
-    } else {
-        enumerate_directory_filtered(src_path, &filter)
-    }?;
-    let copy_jobs: Vec<CopyJob> = initial_entries
-        .into_iter()
-        .map(|entry| CopyJob {
-            entry,
-        })
-        .collect();
-    let (small, medium, large) = categorize_files(copy_jobs);
-    let buffer_sizer = Arc::new(BufferSizer::new());
-    let logger: Arc<dyn Logger + Send + Sync> = Arc::new(NoopLogger);
-    // Small files via tar
-    let mut total_files_copied = 0u64;
-    let mut total_bytes = 0u64;
-    if !small.is_empty() {
-        match process_small_files_tar(&small, src_path, dest_path, false, &*logger) {
-            Ok((f, b)) => {
-                total_files_copied += f;
-                total_bytes += b;
-            }
-            Err(e) => {
-                eprintln!("Error processing small files via TAR: {}", e);
-            }
-        }
-    }
-    // Medium files in parallel
-    if !medium.is_empty() {
-        let pairs = prepare_copy_pairs(&medium, src_path, dest_path);
-        let stats = parallel_copy_files(pairs, buffer_sizer.clone(), false, &*logger);
-        total_files_copied += stats.files_copied;
-        total_bytes += stats.bytes_copied;
-    }
-    // Large files chunked or mmap
-    for job in &large {
-        let dst = compute_destination(&job.entry.path, src_path, dest_path);
-        #[cfg(unix)]
-        let bytes = mmap_copy_file(&job.entry.path, &dst)?;
-        #[cfg(not(unix))]
-        let bytes = chunked_copy_file(
-            &job.entry.path,
-            &dst,
-            &BufferSizer::new(),
-            false,
-            None,
-            &*logger,
-        )?;
-        total_files_copied += 1;
-        total_bytes += bytes;
-    }
-    // Mirror deletions
-    if mirror {
-        let _ = handle_mirror_deletion(src_path, dest_path, &filter, args.verbose, args.dry_run)?;
-    }
-    println!(
-        "Copied {} files ({:.2} MB)",
-        total_files_copied,
-        total_bytes as f64 / 1_048_576.0
-    );
-    Ok(())
+    // Same pipeline as the flag form, so filters, purge limits and the rest all apply
+    run(args)
 }
 
 impl Args {
@@ -987,25 +1747,62 @@ impl Args {
             source: None,
             destination: None,
             threads: self.threads,
+            max_open_files: self.max_open_files,
+            max_errors: self.max_errors,
+            large_file_concurrency: self.large_file_concurrency,
+            pin_threads: self.pin_threads,
+            min_free: self.min_free,
+            io_buffer: self.io_buffer,
             net_workers: self.net_workers,
             net_chunk_mb: self.net_chunk_mb,
             verbose: self.verbose,
             progress: self.progress,
+            no_summary: self.no_summary,
+            slow_report: self.slow_report,
+            progress_interval: self.progress_interval,
             mirror: false,
             delete: false,
+            no_delete: self.no_delete,
+            max_purge: self.max_purge,
+            max_purge_pct: self.max_purge_pct,
+            max_delete: self.max_delete,
+            keep_dest_dotdirs: self.keep_dest_dotdirs,
+            prune_empty_dirs: self.prune_empty_dirs,
+            force: self.force,
+            preserve_atime: self.preserve_atime,
+            preserve_mode: self.preserve_mode,
+            restore_src_atime: self.restore_src_atime,
+            link_dest: self.link_dest.clone(),
+            delay_updates: self.delay_updates,
+            control_file: self.control_file.clone(),
+            strict_case: self.strict_case,
+            metadata_only: self.metadata_only,
+            max_path_len: self.max_path_len,
+            flatten: self.flatten,
+            on_collision: self.on_collision,
+            order: self.order,
+            fsync_dirs: self.fsync_dirs,
             update: false,
             subdirs: self.subdirs,
             empty_dirs: self.empty_dirs,
             no_empty_dirs: self.no_empty_dirs,
             dry_run: self.dry_run,
             exclude_files: self.exclude_files.clone(),
+            filters: self.filters.clone(),
             exclude_dirs: self.exclude_dirs.clone(),
+            exclude_caches: self.exclude_caches,
+            one_file_system: self.one_file_system,
+            strict_scan: self.strict_scan,
             checksum: self.checksum,
+            second_precision: self.second_precision,
             force_tar: self.force_tar,
             no_tar: self.no_tar,
             no_verify: self.no_verify,
             no_restart: self.no_restart,
             // serve_legacy, bind, root removed
+            output: self.output,
+            plan_out: self.plan_out.clone(),
+            list_extras: self.list_extras,
             log_file: self.log_file.clone(),
             sl: self.sl,
             #[cfg(windows)]
@@ -1013,6 +1810,7 @@ impl Args {
             xj: self.xj,
             xjd: self.xjd,
             xjf: self.xjf,
+            broken_symlinks: self.broken_symlinks,
             ludicrous_speed: self.ludicrous_speed,
             never_tell_me_the_odds: self.never_tell_me_the_odds,
             complete_remote: None,
@@ -1021,6 +1819,80 @@ impl Args {
     }
 }
 
+/// Parse a byte size with an optional binary suffix (`512`, `64K`, `10M`, `2G`, `1T`)
+fn parse_size(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim();
+    let (digits, multiplier) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => {
+            let multiplier: u64 = match c.to_ascii_uppercase() {
+                'K' => 1 << 10,
+                'M' => 1 << 20,
+                'G' => 1 << 30,
+                'T' => 1 << 40,
+                _ => return Err(format!("unknown size suffix '{}' in '{}'", c, s)),
+            };
+            (&s[..i], multiplier)
+        }
+        _ => (s, 1),
+    };
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid size '{}'", s))?;
+    value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{}' is too large", s))
+}
+
+/// Parse `--io-buffer`, keeping it within a range the copy loops can sensibly allocate
+fn parse_io_buffer(s: &str) -> std::result::Result<usize, String> {
+    const MIN: u64 = 4 << 10;
+    const MAX: u64 = 256 << 20;
+    let size = parse_size(s)?;
+    if !(MIN..=MAX).contains(&size) {
+        return Err(format!("buffer size '{}' must be between 4K and 256M", s));
+    }
+    Ok(size as usize)
+}
+
+fn buffer_sizer_for(args: &Args) -> BufferSizer {
+    args.io_buffer
+        .map_or_else(BufferSizer::new, BufferSizer::fixed)
+}
+
+/// Read size for `--checksum` comparisons, `None` without `--checksum`
+fn checksum_buffer(args: &Args) -> Option<usize> {
+    args.checksum
+        .then(|| args.io_buffer.unwrap_or(HASH_BUFFER_SIZE))
+}
+
+/// Parse `--threads`: an absolute count, or `N%` of the available cores
+fn parse_thread_count(s: &str) -> std::result::Result<usize, String> {
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    parse_thread_count_with(s, cores)
+}
+
+fn parse_thread_count_with(s: &str, cores: usize) -> std::result::Result<usize, String> {
+    let s = s.trim();
+    match s.strip_suffix('%') {
+        Some(pct) => {
+            let pct: usize = pct
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid thread percentage '{}'", s))?;
+            if pct == 0 {
+                return Err("thread percentage must be greater than 0%".to_string());
+            }
+            // Round up so small percentages still get a thread; never exceed the core count
+            Ok(cores.saturating_mul(pct).div_ceil(100).clamp(1, cores))
+        }
+        None => s
+            .parse()
+            .map_err(|_| format!("invalid thread count '{}'", s)),
+    }
+}
+
 /// Check if path is a network location
 fn is_network_path(_path: &Path) -> bool {
     false
@@ -1061,28 +1933,61 @@ fn should_use_tar(small_files: &[CopyJob], _is_network: bool) -> bool {
 }
 
 /// Copy a single file
-fn copy_single_file(src: &Path, dst: &Path, _is_network: bool, verbose: bool) -> Result<()> {
+#[cfg_attr(windows, allow(unused_variables))]
+fn copy_single_file(
+    src: &Path,
+    dst: &Path,
+    buffer_sizer: &BufferSizer,
+    verbose: bool,
+    progress_interval: std::time::Duration,
+) -> Result<()> {
     if verbose {
         println!("Copying single file...");
     }
-
-    #[cfg(not(windows))]
-    let buffer_sizer = BufferSizer::new();
     #[cfg(windows)]
     let bytes = windows_copyfile(src, dst)?;
+    // A 0/1 file counter never moves during one long copy, so show bytes instead
     #[cfg(not(windows))]
-    let bytes = blit::copy::copy_file(
-        src,
-        dst,
-        &buffer_sizer,
-        false, /* local only */
-        &NoopLogger,
-    )?;
+    let bytes = if verbose {
+        let pb = byte_progress_bar(std::fs::metadata(src)?.len(), progress_interval);
+        let bytes = chunked_copy_file(src, dst, buffer_sizer, false, Some(&pb), &NoopLogger);
+        pb.finish_and_clear();
+        bytes?
+    } else {
+        blit::copy::copy_file(
+            src,
+            dst,
+            buffer_sizer,
+            false, /* local only */
+            &NoopLogger,
+        )?
+    };
 
     println!("Copied {} bytes", bytes);
     Ok(())
 }
 
+/// Redraw rate for progress bars updated at most once per `interval` (indicatif's floor is 1 Hz)
+#[cfg_attr(windows, allow(dead_code))]
+fn progress_refresh_hz(interval: std::time::Duration) -> u8 {
+    (1000 / interval.as_millis().max(1)).clamp(1, 100) as u8
+}
+
+/// Byte-based bar for a single-file copy
+#[cfg(not(windows))]
+fn byte_progress_bar(len: u64, interval: std::time::Duration) -> indicatif::ProgressBar {
+    let pb = indicatif::ProgressBar::with_draw_target(
+        Some(len),
+        indicatif::ProgressDrawTarget::stderr_with_hz(progress_refresh_hz(interval)),
+    );
+    if let Ok(style) = indicatif::ProgressStyle::default_bar()
+        .template("[{elapsed_precise}] {bar:40} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")
+    {
+        pb.set_style(style);
+    }
+    pb
+}
+
 /// Process small files using tar streaming
 fn process_small_files_tar(
     jobs: &[CopyJob],
@@ -1092,9 +1997,26 @@ fn process_small_files_tar(
     logger: &dyn Logger,
 ) -> Result<(u64, u64)> {
     logger.start(src_root, dst_root);
+    let started = Instant::now();
+    // Copy-on-write destinations share the data outright; the first refusal means this
+    // destination cannot clone, so everything after it goes through tar
+    let mut cloned = (0u64, 0u64);
+    let mut try_clone = true;
     // Build explicit file list: (source_path, tar_relative_path)
     let mut file_list: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(jobs.len());
     for job in jobs {
+        if try_clone {
+            let dst = compute_destination(&job.entry.path, src_root, dst_root);
+            match reflink_file(&job.entry.path, &dst) {
+                Ok(true) => {
+                    cloned.0 += 1;
+                    cloned.1 += job.entry.size;
+                    continue;
+                }
+                Ok(false) => try_clone = false,
+                Err(_) => {}
+            }
+        }
         let rel_path = job
             .entry
             .path
@@ -1103,12 +2025,179 @@ fn process_small_files_tar(
             .to_path_buf();
         file_list.push((job.entry.path.clone(), rel_path));
     }
-    let config = TarConfig::default();
-    let result = tar_stream_transfer_list(&file_list, dst_root, &config, false)?;
-    logger.done(result.0, result.1, 0.0);
+    let tarred = if file_list.is_empty() {
+        (0, 0)
+    } else {
+        tar_stream_transfer_list(&file_list, dst_root, &TarConfig::default(), false)?
+    };
+    let result = (cloned.0 + tarred.0, cloned.1 + tarred.1);
+    logger.done(result.0, result.1, started.elapsed().as_secs_f64());
     Ok(result)
 }
 
+/// `--flatten`: copy every file straight into `dest_root`, resolving name clashes per `--on-collision`
+fn run_flattened(
+    jobs: Vec<CopyJob>,
+    dest_root: &Path,
+    args: &Args,
+    logger: &dyn Logger,
+) -> Result<RunOutcome> {
+    if args.mirror || args.delete {
+        anyhow::bail!("--flatten cannot be combined with --mir/--delete");
+    }
+    let (pairs, collisions) = flatten_pairs(jobs, dest_root, args.on_collision);
+    if collisions > 0 {
+        println!("Name collisions: {} ({:?})", collisions, args.on_collision);
+    }
+    if args.dry_run {
+        for (entry, dst) in &pairs {
+            println!("  {} -> {}", entry.path.display(), dst.display());
+        }
+        let bytes: u64 = pairs.iter().map(|(entry, _)| entry.size).sum();
+        println!(
+            "Would transfer {} files, {:.2} GB",
+            pairs.len(),
+            bytes as f64 / 1_073_741_824.0
+        );
+        return Ok(RunOutcome::NotCopied);
+    }
+
+    std::fs::create_dir_all(dest_root)
+        .with_context(|| format!("Failed to create destination {:?}", dest_root))?;
+    let stats = parallel_copy_files(
+        pairs,
+        Arc::new(buffer_sizer_for(args)),
+        false,
+        &OpenFileLimiter::new(args.max_open_files),
+        &ErrorBudget::new(args.max_errors),
+        &PauseControl::new(args.control_file.clone()),
+        logger,
+    );
+    for line in end_of_run_summary(args, &stats) {
+        println!("{}", line);
+    }
+    if !stats.errors.is_empty() {
+        for error in &stats.errors {
+            eprintln!("  - {}", error);
+        }
+        anyhow::bail!("{} files failed to copy", stats.errors.len());
+    }
+    Ok(RunOutcome::Copied)
+}
+
+/// Map every job to `dest_root/<file name>`, in path order so collision handling is repeatable.
+///
+/// Returns the pairs to copy and how many files clashed with an earlier name.
+fn flatten_pairs(
+    mut jobs: Vec<CopyJob>,
+    dest_root: &Path,
+    policy: OnCollision,
+) -> (Vec<(FileEntry, PathBuf)>, usize) {
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+
+    jobs.sort_by(|a, b| a.entry.path.cmp(&b.entry.path));
+    let mut taken: HashMap<OsString, usize> = HashMap::new();
+    let mut pairs: Vec<(FileEntry, PathBuf)> = Vec::with_capacity(jobs.len());
+    let mut collisions = 0;
+    for job in jobs {
+        let name = job
+            .entry
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string();
+        let Some(&index) = taken.get(&name) else {
+            taken.insert(name.clone(), pairs.len());
+            pairs.push((job.entry, dest_root.join(name)));
+            continue;
+        };
+        collisions += 1;
+        match policy {
+            OnCollision::Skip => {}
+            OnCollision::Overwrite => pairs[index].0 = job.entry,
+            OnCollision::Rename => {
+                let renamed = (1..)
+                    .map(|n| numbered_name(&name, n))
+                    .find(|candidate| !taken.contains_key(candidate))
+                    .unwrap_or_default();
+                taken.insert(renamed.clone(), pairs.len());
+                pairs.push((job.entry, dest_root.join(renamed)));
+            }
+        }
+    }
+    (pairs, collisions)
+}
+
+/// `report.txt` -> `report-1.txt`
+fn numbered_name(name: &std::ffi::OsStr, n: usize) -> std::ffi::OsString {
+    let path = Path::new(name);
+    let mut out = path.file_stem().unwrap_or(name).to_os_string();
+    out.push(format!("-{}", n));
+    if let Some(ext) = path.extension() {
+        out.push(".");
+        out.push(ext);
+    }
+    out
+}
+
+/// Split off jobs whose destination path is longer than `max` characters, returning those
+/// destinations so they can be reported
+fn split_overlong_paths(
+    jobs: Vec<CopyJob>,
+    src_root: &Path,
+    dst_root: &Path,
+    max: usize,
+) -> (Vec<CopyJob>, Vec<PathBuf>) {
+    let mut fits = Vec::with_capacity(jobs.len());
+    let mut too_long = Vec::new();
+    for job in jobs {
+        let dst = compute_destination(&job.entry.path, src_root, dst_root);
+        if dst.to_string_lossy().chars().count() > max {
+            too_long.push(dst);
+        } else {
+            fits.push(job);
+        }
+    }
+    (fits, too_long)
+}
+
+/// One warning per source file whose relative path matches an earlier one except for case.
+///
+/// Only meaningful when the destination folds case; otherwise nothing is reported.
+fn case_collision_warnings(
+    jobs: &[CopyJob],
+    src_root: &Path,
+    dest_folds_case: bool,
+) -> Vec<String> {
+    use std::collections::HashMap;
+
+    if !dest_folds_case {
+        return Vec::new();
+    }
+    let mut seen: HashMap<String, &Path> = HashMap::new();
+    let mut warnings = Vec::new();
+    for job in jobs {
+        let rel = job
+            .entry
+            .path
+            .strip_prefix(src_root)
+            .unwrap_or(&job.entry.path);
+        let key = rel.to_string_lossy().to_lowercase();
+        match seen.get(&key) {
+            Some(first) => warnings.push(format!(
+                "{} and {} differ only in case; one would overwrite the other on the destination",
+                first.display(),
+                job.entry.path.display()
+            )),
+            None => {
+                seen.insert(key, &job.entry.path);
+            }
+        }
+    }
+    warnings
+}
+
 /// Prepare source-destination pairs for copying
 fn prepare_copy_pairs(
     files: &[CopyJob],
@@ -1124,6 +2213,384 @@ fn prepare_copy_pairs(
         .collect()
 }
 
+/// Detect a destination that is the source itself or lives inside it.
+///
+/// Errors when both resolve to the same directory. When the destination is nested, returns its
+/// path expressed under `src` so the scan can skip it instead of recursing into the growing copy.
+fn destination_inside_source(src: &Path, dest: &Path) -> Result<Option<PathBuf>> {
+    let src_canon = std::fs::canonicalize(src)
+        .with_context(|| format!("Failed to resolve source {}", src.display()))?;
+    let dest_canon = canonicalize_lenient(dest);
+    if dest_canon == src_canon {
+        anyhow::bail!(
+            "Destination {} is the same directory as source {}",
+            dest.display(),
+            src.display()
+        );
+    }
+    Ok(dest_canon
+        .strip_prefix(&src_canon)
+        .ok()
+        .map(|rel| src.join(rel)))
+}
+
+/// `path` spelled under `root` when it lies inside it (through symlinks or `..`), so it can be
+/// compared with paths found by scanning `root`
+fn relocate_under(path: &Path, root: &Path) -> Option<PathBuf> {
+    let path = canonicalize_lenient(path);
+    let rel = path.strip_prefix(canonicalize_lenient(root)).ok()?;
+    Some(root.join(rel))
+}
+
+/// Canonicalize the longest existing ancestor of `path` and re-append the missing tail
+fn canonicalize_lenient(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canon) = std::fs::canonicalize(current) {
+            return missing.iter().rev().fold(canon, |acc, name| acc.join(name));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                current = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Render the planned operations one per line, sorted by path so runs can be diffed
+///
+/// `+` creates a file, `~` updates one, `-` deletes an extra file and `d` an extra directory.
+fn plan_text(
+    planned: &[&CopyJob],
+    src_root: &Path,
+    dst_root: &Path,
+    deletions: Option<&(Vec<PathBuf>, Vec<PathBuf>)>,
+) -> String {
+    let rel = |path: &Path, root: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    let mut lines: Vec<(String, char)> = planned
+        .iter()
+        .map(|job| {
+            let dst = compute_destination(&job.entry.path, src_root, dst_root);
+            let op = if dst.exists() { '~' } else { '+' };
+            (rel(&job.entry.path, src_root), op)
+        })
+        .collect();
+    if let Some((files, dirs)) = deletions {
+        lines.extend(files.iter().map(|p| (rel(p, dst_root), '-')));
+        lines.extend(dirs.iter().map(|p| (rel(p, dst_root), 'd')));
+    }
+    lines.sort();
+
+    let mut out = String::new();
+    for (path, op) in lines {
+        out.push(op);
+        out.push(' ');
+        out.push_str(&path);
+        out.push('\n');
+    }
+    out
+}
+
+/// Split jobs into those that need copying and those whose destination is already up to date
+fn partition_unchanged(
+    jobs: Vec<CopyJob>,
+    src_root: &Path,
+    dst_root: &Path,
+    checksum: Option<usize>,
+    mtime_slack: std::time::Duration,
+) -> (Vec<CopyJob>, Vec<CopyJob>) {
+    jobs.into_par_iter().partition(|job| {
+        let src = &job.entry.path;
+        let dst = compute_destination(src, src_root, dst_root);
+        file_needs_copy(src, &dst, checksum, mtime_slack).unwrap_or(true)
+    })
+}
+
+/// Split off jobs whose `--link-dest` counterpart is identical (same size and mtime, or same
+/// content with `--checksum`); the second half is hard linked rather than copied
+fn partition_linkable(
+    jobs: Vec<CopyJob>,
+    src_root: &Path,
+    link_root: &Path,
+    checksum: Option<usize>,
+    mtime_slack: std::time::Duration,
+) -> (Vec<CopyJob>, Vec<CopyJob>) {
+    jobs.into_par_iter().partition(|job| {
+        let candidate = compute_destination(&job.entry.path, src_root, link_root);
+        !link_candidate_matches(&job.entry.path, &candidate, checksum, mtime_slack)
+    })
+}
+
+fn link_candidate_matches(
+    src: &Path,
+    candidate: &Path,
+    checksum: Option<usize>,
+    mtime_slack: std::time::Duration,
+) -> bool {
+    std::fs::metadata(candidate).is_ok_and(|md| md.is_file())
+        && file_needs_copy(src, candidate, checksum, mtime_slack).is_ok_and(|copy| !copy)
+}
+
+/// Sort jobs for `--order`; ties fall back to path order so runs are repeatable
+fn order_jobs(jobs: &mut [CopyJob], order: CopyOrder) {
+    match order {
+        CopyOrder::Path => jobs.sort_by(|a, b| a.entry.path.cmp(&b.entry.path)),
+        CopyOrder::Largest => {
+            jobs.sort_by(|a, b| (b.entry.size, &a.entry.path).cmp(&(a.entry.size, &b.entry.path)))
+        }
+        CopyOrder::Smallest => {
+            jobs.sort_by(|a, b| (a.entry.size, &a.entry.path).cmp(&(b.entry.size, &b.entry.path)))
+        }
+    }
+}
+
+/// `--pin-threads`: a Rayon start handler that pins worker `i` to the `i`-th CPU this process
+/// may run on, wrapping around when there are more workers than CPUs
+fn cpu_pinner() -> impl Fn(usize) + Send + Sync + 'static {
+    let cpus = allowed_cpus();
+    move |index| {
+        if !cpus.is_empty() {
+            pin_current_thread(cpus[index % cpus.len()]);
+        }
+    }
+}
+
+/// CPUs in the process affinity mask, so `taskset` and cpusets are respected
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> Vec<usize> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::cpu_set_t>();
+    if unsafe { libc::sched_getaffinity(0, size, &mut set) } != 0 {
+        return Vec::new();
+    }
+    (0..libc::CPU_SETSIZE as usize)
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allowed_cpus() -> Vec<usize> {
+    Vec::new()
+}
+
+/// Best effort: a failure leaves the thread floating as before
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cpu: usize) {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    unsafe { libc::CPU_SET(cpu, &mut set) };
+    let size = std::mem::size_of::<libc::cpu_set_t>();
+    let _ = unsafe { libc::sched_setaffinity(0, size, &set) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_cpu: usize) {}
+
+/// `--large-file-concurrency`: each large copy holds a source and a destination handle, so N
+/// copies at once is an open-file limit of 2N
+fn large_copy_slots(concurrency: usize) -> OpenFileLimiter {
+    OpenFileLimiter::new(concurrency * 2)
+}
+
+/// Staging directory for `--delay-updates`, created inside the destination root. It is never
+/// copied from a source or purged from a destination.
+const STAGING_DIR: &str = ".blit-staging";
+/// File marking a staging directory as blit's own, so only those are ever removed
+const STAGING_MARKER: &str = "blit-staging";
+/// Subdirectory of the staging directory holding the staged files, apart from the marker
+const STAGING_TREE: &str = "tree";
+
+/// Create the `--delay-updates` staging directory under `dest_root` and return the directory
+/// files are staged in. A marked leftover from an interrupted run is cleared first; anything
+/// else already at that path is left alone and fails the run.
+fn prepare_staging(dest_root: &Path) -> Result<PathBuf> {
+    let staging = dest_root.join(STAGING_DIR);
+    if std::fs::symlink_metadata(&staging).is_ok() {
+        if !staging.join(STAGING_MARKER).is_file() {
+            anyhow::bail!(
+                "{:?} was not created by blit; move it aside to use --delay-updates",
+                staging
+            );
+        }
+        std::fs::remove_dir_all(&staging).with_context(|| format!("remove {:?}", staging))?;
+    }
+    let tree = staging.join(STAGING_TREE);
+    std::fs::create_dir_all(&tree).with_context(|| format!("create {:?}", tree))?;
+    std::fs::write(staging.join(STAGING_MARKER), b"")
+        .with_context(|| format!("mark {:?}", staging))?;
+    Ok(tree)
+}
+
+/// Move every file staged under `tree` to the same relative path under `dest_root`, then
+/// remove the staging directory; returns the destination directories that gained entries
+fn commit_staged(tree: &Path, dest_root: &Path) -> Result<std::collections::BTreeSet<PathBuf>> {
+    // The root loses the staging directory itself
+    let mut changed = std::collections::BTreeSet::from([dest_root.to_path_buf()]);
+    for entry in enumerate_directory_filtered(tree, &FileFilter::default())? {
+        let dst = compute_destination(&entry.path, tree, dest_root);
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)?;
+            changed.insert(parent.to_path_buf());
+        }
+        std::fs::rename(&entry.path, &dst)
+            .with_context(|| format!("move staged {:?} into place (--delay-updates)", dst))?;
+    }
+    let staging = dest_root.join(STAGING_DIR);
+    std::fs::remove_dir_all(&staging).with_context(|| format!("remove {:?}", staging))?;
+    Ok(changed)
+}
+
+/// Replace `dst` with a hard link to `basis`, creating parent directories as needed
+fn hard_link_into_place(basis: &Path, dst: &Path) -> Result<()> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+    if std::fs::symlink_metadata(dst).is_ok() {
+        std::fs::remove_file(dst).with_context(|| format!("Failed to replace {:?}", dst))?;
+    }
+    std::fs::hard_link(basis, dst)
+        .with_context(|| format!("Failed to hard link {:?} to {:?}", dst, basis))
+}
+
+/// Give skipped (content-identical) destinations the source mtime where it differs
+fn correct_skipped_mtimes(skipped: &[CopyJob], src_root: &Path, dst_root: &Path) -> u64 {
+    let mut fixed = 0u64;
+    for job in skipped {
+        let dst = compute_destination(&job.entry.path, src_root, dst_root);
+        let (Ok(src_md), Ok(dst_md)) =
+            (std::fs::metadata(&job.entry.path), std::fs::metadata(&dst))
+        else {
+            continue;
+        };
+        let src_mtime = filetime::FileTime::from_last_modification_time(&src_md);
+        if src_mtime != filetime::FileTime::from_last_modification_time(&dst_md)
+            && filetime::set_file_mtime(&dst, src_mtime).is_ok()
+        {
+            fixed += 1;
+        }
+    }
+    fixed
+}
+
+/// `--preserve-mode`: give each destination the source's permissions where they differ,
+/// returning how many were changed
+fn copy_modes(sources: &[PathBuf], src_root: &Path, dst_root: &Path, stats: &mut CopyStats) -> u64 {
+    let mut updated = 0u64;
+    for src in sources {
+        let dst = compute_destination(src, src_root, dst_root);
+        let (Ok(src_md), Ok(dst_md)) = (std::fs::metadata(src), std::fs::metadata(&dst)) else {
+            continue;
+        };
+        if src_md.permissions() == dst_md.permissions() {
+            continue;
+        }
+        match std::fs::set_permissions(&dst, src_md.permissions()) {
+            Ok(()) => updated += 1,
+            Err(e) => stats.add_error(format!("Failed to set mode on {:?}: {}", dst, e)),
+        }
+    }
+    updated
+}
+
+/// `--metadata-only`: copy mtime and permissions onto destinations whose data already matches,
+/// returning how many were changed and how many were skipped as missing or different
+fn apply_metadata_only(
+    jobs: &[CopyJob],
+    src_root: &Path,
+    dst_root: &Path,
+    checksum: Option<usize>,
+) -> (u64, u64) {
+    let (mut fixed, mut untouched) = (0u64, 0u64);
+    for job in jobs {
+        let dst = compute_destination(&job.entry.path, src_root, dst_root);
+        let (Ok(src_md), Ok(dst_md)) =
+            (std::fs::metadata(&job.entry.path), std::fs::metadata(&dst))
+        else {
+            untouched += 1;
+            continue;
+        };
+        let differs = |buffer_size| {
+            file_needs_copy(
+                &job.entry.path,
+                &dst,
+                Some(buffer_size),
+                std::time::Duration::ZERO,
+            )
+            .unwrap_or(true)
+        };
+        let same_data =
+            dst_md.is_file() && src_md.len() == dst_md.len() && !checksum.is_some_and(differs);
+        if !same_data {
+            untouched += 1;
+            continue;
+        }
+        let mut changed = false;
+        let src_mtime = filetime::FileTime::from_last_modification_time(&src_md);
+        if src_mtime != filetime::FileTime::from_last_modification_time(&dst_md) {
+            changed |= filetime::set_file_mtime(&dst, src_mtime).is_ok();
+        }
+        // Permissions last, so a read-only source mode cannot block the mtime update
+        if src_md.permissions() != dst_md.permissions() {
+            changed |= std::fs::set_permissions(&dst, src_md.permissions()).is_ok();
+        }
+        if changed {
+            fixed += 1;
+        }
+    }
+    (fixed, untouched)
+}
+
+/// Dry-run total over the files that would actually be created or updated
+fn dry_run_total_line(planned: &[&CopyJob]) -> String {
+    let bytes: u64 = planned.iter().map(|job| job.entry.size).sum();
+    format!(
+        "Would transfer {} files, {:.2} GB",
+        planned.len(),
+        bytes as f64 / 1_073_741_824.0
+    )
+}
+
+/// Describe a planned copy for the dry-run listing, including how an existing destination differs
+fn dry_run_line(src: &Path, dst: &Path) -> String {
+    fn mtime(md: &std::fs::Metadata) -> String {
+        md.modified()
+            .map(|t| {
+                chrono::DateTime::<chrono::Local>::from(t)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| "?".to_string())
+    }
+
+    let src_md = match std::fs::metadata(src) {
+        Ok(md) => md,
+        Err(_) => return format!("{} (unreadable)", src.display()),
+    };
+    match std::fs::metadata(dst) {
+        Ok(dst_md) => format!(
+            "{} (update: {} → {} bytes, {} → {})",
+            src.display(),
+            dst_md.len(),
+            src_md.len(),
+            mtime(&dst_md),
+            mtime(&src_md)
+        ),
+        Err(_) => format!("{} (new: {} bytes)", src.display(), src_md.len()),
+    }
+}
+
 /// Compute destination path for a file
 fn compute_destination(src_file: &Path, src_root: &Path, dst_root: &Path) -> PathBuf {
     if let Ok(rel_path) = src_file.strip_prefix(src_root) {
@@ -1133,70 +2600,111 @@ fn compute_destination(src_file: &Path, src_root: &Path, dst_root: &Path) -> Pat
     }
 }
 
-/// Handle mirror mode deletion (delete extra files in destination)
-fn handle_mirror_deletion(
-    source: &Path,
-    destination: &Path,
-    filter: &FileFilter,
-    verbose: bool,
-    dry_run: bool,
-) -> Result<(u64, u64)> {
-    use std::collections::HashSet;
-
-    // Get all files that should exist (from source)
-    let source_entries = enumerate_directory_filtered(source, filter)?;
-    #[cfg(windows)]
-    fn keyify(p: &Path) -> String {
-        p.to_string_lossy().to_ascii_lowercase()
-    }
-    #[cfg(not(windows))]
-    fn keyify(p: &Path) -> String {
-        p.to_string_lossy().to_string()
-    }
-
-    let mut source_files: HashSet<String> = HashSet::new();
-    let mut source_dirs: HashSet<String> = HashSet::new();
+/// Safety rails against mass deletion (`--max-purge`, `--max-purge-pct`, `--max-delete`)
+///
+/// The purge limits abort the run; `max_delete` only suppresses the deletions.
+#[derive(Debug, Clone, Copy, Default)]
+struct PurgeLimits {
+    max_files: Option<usize>,
+    max_pct: Option<f64>,
+    max_delete: Option<usize>,
+}
 
-    for entry in &source_entries {
-        let rel_path = entry.path.strip_prefix(source).unwrap_or(&entry.path);
-        let dest_path = destination.join(rel_path);
+/// Outcome of the mirror deletion pass
+#[derive(Debug, Default)]
+struct DeletionStats {
+    files: u64,
+    dirs: u64,
+    /// Planned deletion count when `--max-delete` suppressed the pass
+    suppressed: Option<usize>,
+    /// Directories that lost an entry, for `--fsync-dirs`
+    parents: std::collections::BTreeSet<PathBuf>,
+}
 
-        if entry.is_directory {
-            source_dirs.insert(keyify(&dest_path));
-        } else {
-            source_files.insert(keyify(&dest_path));
-            // Also track the parent directories
-            if let Some(parent) = dest_path.parent() {
-                let mut current = parent;
-                while current != destination && current.parent().is_some() {
-                    source_dirs.insert(keyify(current));
-                    current = current.parent().context("Failed to get parent directory")?;
-                }
-            }
+impl DeletionStats {
+    /// Error to exit with once the rest of the run has finished
+    fn suppressed_error(&self, limits: &PurgeLimits) -> Result<()> {
+        match (self.suppressed, limits.max_delete) {
+            (Some(planned), Some(max)) => anyhow::bail!(
+                "Skipped {} deletions: more than --max-delete {}",
+                planned,
+                max
+            ),
+            _ => Ok(()),
         }
     }
+}
 
-    // Scan destination to find extra files
-    if !destination.exists() {
-        return Ok((0, 0)); // Nothing to delete
+impl PurgeLimits {
+    fn from_args(args: &Args) -> Self {
+        Self {
+            max_files: args.max_purge,
+            max_pct: args.max_purge_pct,
+            max_delete: args.max_delete,
+        }
     }
 
-    let dest_entries = enumerate_directory_filtered(destination, &FileFilter::default())?;
-    let mut files_to_delete = Vec::new();
-    let mut dirs_to_delete = Vec::new();
-
-    for entry in &dest_entries {
-        if entry.is_directory {
-            if !source_dirs.contains(&keyify(&entry.path)) {
-                dirs_to_delete.push(entry.path.clone());
+    /// Fail if `planned` extra files exceed either limit; the percentage is of the
+    /// `total` files in the destination, directories not counted.
+    fn check(&self, planned: usize, total: usize) -> Result<()> {
+        if let Some(max) = self.max_files {
+            if planned > max {
+                anyhow::bail!(
+                    "Refusing to purge {} files (--max-purge {}); nothing was deleted",
+                    planned,
+                    max
+                );
+            }
+        }
+        if let Some(pct) = self.max_pct {
+            let share = if total == 0 {
+                0.0
+            } else {
+                planned as f64 * 100.0 / total as f64
+            };
+            if share > pct {
+                anyhow::bail!(
+                    "Refusing to purge {} of {} destination files ({:.1}% > --max-purge-pct {}); nothing was deleted",
+                    planned,
+                    total,
+                    share,
+                    pct
+                );
             }
-        } else if !source_files.contains(&keyify(&entry.path)) {
-            files_to_delete.push(entry.path.clone());
         }
+        Ok(())
     }
+}
+
+/// Handle mirror mode deletion (delete extra files in destination)
+fn handle_mirror_deletion(
+    source: &Path,
+    destination: &Path,
+    filter: &FileFilter,
+    verbose: bool,
+    dry_run: bool,
+    force: bool,
+    limits: &PurgeLimits,
+) -> Result<DeletionStats> {
+    let (files_to_delete, mut dirs_to_delete, dest_files) =
+        plan_mirror_deletion_counted(source, destination, filter)?;
+    limits.check(files_to_delete.len(), dest_files)?;
 
     let total_deletions = files_to_delete.len() + dirs_to_delete.len();
 
+    if let Some(max) = limits.max_delete {
+        if total_deletions > max {
+            eprintln!(
+                "Warning: {} deletions planned, more than --max-delete {}; skipping all deletions",
+                total_deletions, max
+            );
+            return Ok(DeletionStats {
+                suppressed: Some(total_deletions),
+                ..Default::default()
+            });
+        }
+    }
+
     if dry_run {
         if total_deletions > 0 {
             println!("\n=== Mirror Mode - Would Delete ===");
@@ -1229,12 +2737,17 @@ fn handle_mirror_deletion(
         } else {
             println!("\n=== Mirror Mode - No extra files to delete ===");
         }
-        return Ok((files_to_delete.len() as u64, dirs_to_delete.len() as u64));
+        return Ok(DeletionStats {
+            files: files_to_delete.len() as u64,
+            dirs: dirs_to_delete.len() as u64,
+            ..Default::default()
+        });
     }
 
     // Actually delete files and directories
     let mut deleted_files = 0u64;
     let mut deleted_dirs = 0u64;
+    let mut parents = std::collections::BTreeSet::new();
 
     // Delete files first
     for path in files_to_delete.iter() {
@@ -1244,9 +2757,16 @@ fn handle_mirror_deletion(
         #[cfg(windows)]
         blit::win_fs::clear_readonly_recursive(path);
 
-        match std::fs::remove_file(path) {
+        let removed = match std::fs::remove_file(path) {
+            Err(e) if force && e.kind() == std::io::ErrorKind::PermissionDenied => {
+                retry_in_writable_parent(path, || std::fs::remove_file(path))
+            }
+            other => other,
+        };
+        match removed {
             Ok(_) => {
                 deleted_files += 1;
+                parents.extend(path.parent().map(Path::to_path_buf));
                 if verbose {
                     println!("Deleted file: {}", path.display());
                 }
@@ -1268,9 +2788,16 @@ fn handle_mirror_deletion(
         #[cfg(windows)]
         blit::win_fs::clear_readonly_recursive(path);
 
-        match std::fs::remove_dir(path) {
+        let removed = match std::fs::remove_dir(path) {
+            Err(e) if force && e.kind() == std::io::ErrorKind::PermissionDenied => {
+                retry_in_writable_parent(path, || std::fs::remove_dir(path))
+            }
+            other => other,
+        };
+        match removed {
             Ok(_) => {
                 deleted_dirs += 1;
+                parents.extend(path.parent().map(Path::to_path_buf));
                 if verbose {
                     println!("Deleted directory: {}", path.display());
                 }
@@ -1286,15 +2813,430 @@ fn handle_mirror_deletion(
         }
     }
 
-    Ok((deleted_files, deleted_dirs))
+    Ok(DeletionStats {
+        files: deleted_files,
+        dirs: deleted_dirs,
+        suppressed: None,
+        parents,
+    })
+}
+
+/// Recreate the source symlink `link` at the destination with the same (possibly dangling) target
+fn recreate_symlink(link: &Path, src_root: &Path, dst_root: &Path) -> Result<()> {
+    let target = std::fs::read_link(link).with_context(|| format!("read link {:?}", link))?;
+    let dst = compute_destination(link, src_root, dst_root);
+    if let Ok(md) = std::fs::symlink_metadata(&dst) {
+        if md.file_type().is_symlink() && std::fs::read_link(&dst).ok().as_ref() == Some(&target) {
+            return Ok(());
+        }
+        std::fs::remove_file(&dst).with_context(|| format!("replace {:?}", dst))?;
+    }
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, &dst)
+        .with_context(|| format!("create symlink {:?}", dst))?;
+    #[cfg(windows)]
+    blit::win_fs::create_symlink(&target, &dst)
+        .with_context(|| format!("create symlink {:?}", dst))?;
+    Ok(())
+}
+
+/// Source directories from each file's parent up to `src_root`, deepest first
+fn ancestor_dirs<'a>(jobs: impl Iterator<Item = &'a CopyJob>, src_root: &Path) -> Vec<PathBuf> {
+    use std::collections::HashSet;
+
+    let mut seen = HashSet::new();
+    for job in jobs {
+        for dir in job.entry.path.ancestors().skip(1) {
+            if !dir.starts_with(src_root) || !seen.insert(dir.to_path_buf()) {
+                break;
+            }
+        }
+    }
+    let mut dirs: Vec<PathBuf> = seen.into_iter().collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.components().count()));
+    dirs
+}
+
+/// Source directories below `src_root` that hold any of `jobs`; the root itself is never counted
+fn planned_dirs(jobs: &[CopyJob], src_root: &Path) -> Vec<PathBuf> {
+    let mut dirs = ancestor_dirs(jobs.iter(), src_root);
+    dirs.retain(|d| d != src_root);
+    dirs
+}
+
+/// Source directories whose destination counterpart does not exist yet
+fn missing_dest_dirs(dirs: &[PathBuf], src_root: &Path, dst_root: &Path) -> Vec<PathBuf> {
+    dirs.iter()
+        .filter(|d| !compute_destination(d, src_root, dst_root).exists())
+        .cloned()
+        .collect()
+}
+
+/// Record how many of `new_dirs` the copy actually created; the rest of `dirs` already existed
+fn count_dirs(
+    stats: &mut CopyStats,
+    dirs: &[PathBuf],
+    new_dirs: &[PathBuf],
+    src_root: &Path,
+    dst_root: &Path,
+) {
+    stats.dirs_created = new_dirs
+        .iter()
+        .filter(|d| compute_destination(d, src_root, dst_root).is_dir())
+        .count() as u64;
+    stats.dirs_existing = (dirs.len() - new_dirs.len()) as u64;
+}
+
+/// Give destination directories the mtime of their source counterpart, deepest first so that
+/// fixing a child does not disturb an already-fixed parent
+fn restore_dir_mtimes(dirs: &[PathBuf], src_root: &Path, dst_root: &Path) {
+    for src_dir in dirs {
+        let dst_dir = compute_destination(src_dir, src_root, dst_root);
+        if let Ok(md) = std::fs::metadata(src_dir) {
+            let mtime = filetime::FileTime::from_last_modification_time(&md);
+            let _ = filetime::set_file_mtime(&dst_dir, mtime);
+        }
+    }
+}
+
+/// Retry `op` on `path` after making its parent directory owner-writable, then restore the parent
+fn retry_in_writable_parent(
+    path: &Path,
+    op: impl FnOnce() -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(p) => p,
+        None => return op(),
+    };
+    let original = add_owner_write(parent)?;
+    let result = op();
+    if let Some(perms) = original {
+        let _ = std::fs::set_permissions(parent, perms);
+    }
+    result
+}
+
+/// Make existing read-only destinations (and read-only parent directories) owner-writable.
+///
+/// Returns the original permissions, files before directories, for `relock_destinations`.
+fn unlock_destinations<'a>(
+    jobs: impl Iterator<Item = &'a CopyJob>,
+    src_root: &Path,
+    dst_root: &Path,
+) -> Vec<(PathBuf, std::fs::Permissions)> {
+    use std::collections::HashSet;
+
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut seen_dirs = HashSet::new();
+    for job in jobs {
+        let dst = compute_destination(&job.entry.path, src_root, dst_root);
+        if !dst.exists() {
+            continue;
+        }
+        if let Ok(Some(perms)) = add_owner_write(&dst) {
+            files.push((dst.clone(), perms));
+        }
+        if let Some(parent) = dst.parent() {
+            if seen_dirs.insert(parent.to_path_buf()) {
+                if let Ok(Some(perms)) = add_owner_write(parent) {
+                    dirs.push((parent.to_path_buf(), perms));
+                }
+            }
+        }
+    }
+    files.extend(dirs);
+    files
+}
+
+/// Put back the permissions recorded by `unlock_destinations`
+fn relock_destinations(unlocked: Vec<(PathBuf, std::fs::Permissions)>) {
+    for (path, perms) in unlocked {
+        if let Err(e) = std::fs::set_permissions(&path, perms) {
+            eprintln!("Failed to restore permissions on {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Relocks what `unlock_destinations` opened up when dropped, so every exit path restores it
+struct Relock(Vec<(PathBuf, std::fs::Permissions)>);
+
+impl Drop for Relock {
+    fn drop(&mut self) {
+        relock_destinations(std::mem::take(&mut self.0));
+    }
+}
+
+/// Destination files that a mirror run would delete, with their sizes, sorted by path
+fn list_extras(
+    source: &Path,
+    destination: &Path,
+    filter: &FileFilter,
+) -> Result<Vec<(PathBuf, u64)>> {
+    let (files, _dirs) = plan_mirror_deletion(source, destination, filter)?;
+    let mut extras: Vec<(PathBuf, u64)> = files
+        .into_iter()
+        .map(|p| {
+            let size = std::fs::symlink_metadata(&p).map(|m| m.len()).unwrap_or(0);
+            (p, size)
+        })
+        .collect();
+    extras.sort();
+    Ok(extras)
+}
+
+/// Remove empty destination directories that have no counterpart in the source (deepest first)
+///
+/// Directories mirror deletion leaves alone are kept too: `--xd` exclusions, kept dot-directories
+/// and those holding a protected file. The parents of pruned directories are added to `parents`.
+fn prune_empty_dirs(
+    source: &Path,
+    destination: &Path,
+    filter: &FileFilter,
+    verbose: bool,
+    parents: &mut std::collections::BTreeSet<PathBuf>,
+) -> Result<u64> {
+    use walkdir::WalkDir;
+
+    let mut pruned = 0u64;
+    for entry in WalkDir::new(destination)
+        .min_depth(1)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let rel_path = match path.strip_prefix(destination) {
+            Ok(rel) => rel,
+            Err(_) => continue,
+        };
+        if source.join(rel_path).is_dir()
+            || !filter.should_include_dir(path, rel_path)
+            || rel_path.starts_with(STAGING_DIR)
+        {
+            continue;
+        }
+        // The dot-directory itself as well as anything under it
+        let top = rel_path.components().next().map(|c| c.as_os_str());
+        let in_dotdir =
+            top.is_some_and(|t| t.to_string_lossy().starts_with('.') && !source.join(t).exists());
+        if filter.keep_dest_dotdirs && in_dotdir {
+            continue;
+        }
+        if filter.protected_files.iter().any(|p| p.starts_with(path)) {
+            continue;
+        }
+        let is_empty = std::fs::read_dir(path)
+            .map(|mut it| it.next().is_none())
+            .unwrap_or(false);
+        if !is_empty {
+            continue;
+        }
+
+        #[cfg(windows)]
+        blit::win_fs::clear_readonly_recursive(path);
+
+        match std::fs::remove_dir(path) {
+            Ok(_) => {
+                pruned += 1;
+                parents.extend(path.parent().map(Path::to_path_buf));
+                if verbose {
+                    println!("Pruned empty directory: {}", path.display());
+                }
+            }
+            Err(e) => {
+                if verbose {
+                    eprintln!("Failed to prune directory {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+    Ok(pruned)
+}
+
+/// Enumerate the source (filtered) and the destination (unfiltered) concurrently.
+///
+/// The two walks touch different trees, often on different devices, so overlapping them hides
+/// most of the destination scan cost on large mirrors.
+fn scan_source_and_destination(
+    source: &Path,
+    filter: &FileFilter,
+    destination: &Path,
+) -> Result<(Vec<FileEntry>, Vec<FileEntry>)> {
+    let dest_filter = FileFilter {
+        exclude_paths: vec![destination.join(STAGING_DIR)],
+        ..Default::default()
+    };
+    let (source_entries, dest_entries) = rayon::join(
+        || enumerate_directory_filtered(source, filter),
+        || enumerate_directory_filtered(destination, &dest_filter),
+    );
+    Ok((source_entries?, dest_entries?))
+}
+
+/// Compute the extra files and directories under `destination` that are not present in `source`.
+///
+/// Both trees are keyed by their path relative to their own root, so spelling differences in the
+/// roots (trailing separators, `./` prefixes) cannot make the destination root look like an extra.
+fn plan_mirror_deletion(
+    source: &Path,
+    destination: &Path,
+    filter: &FileFilter,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    plan_mirror_deletion_counted(source, destination, filter).map(|(files, dirs, _)| (files, dirs))
+}
+
+/// [`plan_mirror_deletion`] plus the number of files the destination scan found
+fn plan_mirror_deletion_counted(
+    source: &Path,
+    destination: &Path,
+    filter: &FileFilter,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>, usize)> {
+    use std::collections::HashSet;
+
+    #[cfg(windows)]
+    fn keyify(p: &Path) -> String {
+        p.to_string_lossy().to_ascii_lowercase()
+    }
+    #[cfg(not(windows))]
+    fn keyify(p: &Path) -> String {
+        p.to_string_lossy().to_string()
+    }
+
+    // Scan destination to find extra files
+    if !destination.exists() {
+        return Ok((Vec::new(), Vec::new(), 0)); // Nothing to delete
+    }
+
+    // Get all files that should exist (from source) while the destination is scanned
+    let (source_entries, dest_entries) = scan_source_and_destination(source, filter, destination)?;
+    let mut source_files: HashSet<String> = HashSet::new();
+    let mut source_dirs: HashSet<String> = HashSet::new();
+
+    for entry in &source_entries {
+        let rel_path = entry.path.strip_prefix(source).unwrap_or(&entry.path);
+        if entry.is_directory {
+            source_dirs.insert(keyify(rel_path));
+        } else {
+            source_files.insert(keyify(rel_path));
+            // Also track the parent directories
+            for ancestor in rel_path.ancestors().skip(1) {
+                if !ancestor.as_os_str().is_empty() {
+                    source_dirs.insert(keyify(ancestor));
+                }
+            }
+        }
+    }
+
+    let mut files_to_delete = Vec::new();
+    let mut dirs_to_delete = Vec::new();
+
+    for entry in &dest_entries {
+        let rel_path = match entry.path.strip_prefix(destination) {
+            Ok(rel) => rel,
+            Err(_) => continue,
+        };
+        // The destination root itself is never a deletion candidate
+        if rel_path.as_os_str().is_empty() {
+            continue;
+        }
+        if filter.keep_dest_dotdirs && in_dest_only_dotdir(rel_path, source) {
+            continue;
+        }
+        // Includes the directories holding a protected file
+        if filter
+            .protected_files
+            .iter()
+            .any(|p| p.starts_with(&entry.path))
+        {
+            continue;
+        }
+        if entry.is_directory {
+            if !source_dirs.contains(&keyify(rel_path)) {
+                dirs_to_delete.push(entry.path.clone());
+            }
+        } else if !source_files.contains(&keyify(rel_path)) {
+            files_to_delete.push(entry.path.clone());
+        }
+    }
+
+    let dest_files = dest_entries.iter().filter(|e| !e.is_directory).count();
+    Ok((files_to_delete, dirs_to_delete, dest_files))
+}
+
+/// True if `rel` lies in a top-level hidden directory that the source does not have
+fn in_dest_only_dotdir(rel: &Path, source: &Path) -> bool {
+    let mut components = rel.components();
+    let Some(first) = components.next() else {
+        return false;
+    };
+    let is_dotdir = first.as_os_str().to_string_lossy().starts_with('.');
+    // A top-level dot-file is a file, not a protected directory
+    is_dotdir && components.next().is_some() && !source.join(first).exists()
 }
 
 // Interactivity removed: previous resume/restart logic deleted for non-interactive behavior
 
+/// The summary a text-mode run ends with; `--progress` only changes the live output, so the
+/// summary is dropped by `--no-summary` alone
+fn end_of_run_summary(args: &Args, stats: &CopyStats) -> Vec<String> {
+    if args.no_summary {
+        return Vec::new();
+    }
+    summary_lines(stats)
+}
+
+/// Build the end-of-run summary.
+///
+/// Files/sec and MB/s are reported separately: small-file runs are bound by per-file overhead,
+/// large-file runs by bandwidth, and a single blended figure hides which one applies.
+fn summary_lines(stats: &CopyStats) -> Vec<String> {
+    let secs = stats.elapsed.as_secs_f64().max(f64::EPSILON);
+    let mut lines = vec![
+        "=== Copy Complete ===".to_string(),
+        format!("Files copied: {}", stats.files_copied),
+    ];
+    if stats.files_skipped > 0 {
+        lines.push(format!("Files skipped (same): {}", stats.files_skipped));
+    }
+    if stats.files_linked > 0 {
+        lines.push(format!("Files hard linked: {}", stats.files_linked));
+    }
+    if stats.dirs_created + stats.dirs_existing > 0 {
+        lines.push(format!(
+            "Directories created: {} ({} already existed)",
+            stats.dirs_created, stats.dirs_existing
+        ));
+    }
+    lines.extend([
+        format!(
+            "Total size: {:.2} GB",
+            stats.bytes_copied as f64 / 1_073_741_824.0
+        ),
+        format!("Time: {:.2}s", stats.elapsed.as_secs_f64()),
+        format!("Files/sec: {:.1}", stats.files_copied as f64 / secs),
+        format!(
+            "Throughput: {:.2} MB/s",
+            (stats.bytes_copied as f64 / 1_048_576.0) / secs
+        ),
+    ]);
+    lines
+}
+
 /// Merge copy statistics
 fn merge_stats(total: &mut CopyStats, other: CopyStats) {
     total.files_copied += other.files_copied;
     total.bytes_copied += other.bytes_copied;
+    total.files_skipped += other.files_skipped;
+    total.files_linked += other.files_linked;
+    total.dirs_created += other.dirs_created;
+    total.dirs_existing += other.dirs_existing;
     total.errors.extend(other.errors);
 }
 
@@ -1357,14 +3299,127 @@ fn verify_trees(src: &Path, dest: &Path, checksum: bool) -> Result<VerifySummary
     }
 }
 
+/// Compare one file present on both sides. Sizes are checked first, so checksum mode only
+/// hashes pairs that could still be identical.
+fn pair_differs(
+    left: &FileEntry,
+    right: &FileEntry,
+    checksum: bool,
+    hash: &mut dyn FnMut(&Path) -> Result<[u8; 32]>,
+) -> Result<bool> {
+    if left.size != right.size {
+        return Ok(true);
+    }
+    if !checksum {
+        return Ok(false);
+    }
+    Ok(hash(&left.path)? != hash(&right.path)?)
+}
+
+/// Relative paths, each sorted, that differ between two trees
+#[derive(Debug, Default, PartialEq)]
+struct TreeDiff {
+    only_a: Vec<String>,
+    only_b: Vec<String>,
+    changed: Vec<String>,
+}
+
+impl TreeDiff {
+    fn is_empty(&self) -> bool {
+        self.only_a.is_empty() && self.only_b.is_empty() && self.changed.is_empty()
+    }
+
+    /// One `<`, `>` or `M` line per path, merged in path order so output is stable across runs
+    fn lines(&self) -> Vec<String> {
+        let mut tagged: Vec<(&str, char)> = self
+            .only_a
+            .iter()
+            .map(|p| (p.as_str(), '<'))
+            .chain(self.only_b.iter().map(|p| (p.as_str(), '>')))
+            .chain(self.changed.iter().map(|p| (p.as_str(), 'M')))
+            .collect();
+        tagged.sort();
+        tagged
+            .into_iter()
+            .map(|(path, tag)| format!("{} {}", tag, path))
+            .collect()
+    }
+}
+
+/// Compare two local trees file by file, by size or (with `checksum`) by content
+fn diff_trees(a: &Path, b: &Path, checksum: bool) -> Result<TreeDiff> {
+    use std::collections::BTreeMap;
+
+    let files = |root: &Path| -> Result<BTreeMap<String, FileEntry>> {
+        let entries = enumerate_directory_filtered(root, &FileFilter::default())
+            .with_context(|| format!("Failed to enumerate {}", root.display()))?;
+        Ok(entries
+            .into_iter()
+            .filter(|e| !e.is_directory)
+            .map(|e| (slash_relative(&e.path, root), e))
+            .collect())
+    };
+    let left = files(a)?;
+    let right = files(b)?;
+
+    let mut diff = TreeDiff::default();
+    for (rel, l) in &left {
+        match right.get(rel) {
+            Some(r) if pair_differs(l, r, checksum, &mut hash_file)? => {
+                diff.changed.push(rel.clone())
+            }
+            Some(_) => {}
+            None => diff.only_a.push(rel.clone()),
+        }
+    }
+    diff.only_b = right
+        .keys()
+        .filter(|rel| !left.contains_key(*rel))
+        .cloned()
+        .collect();
+    Ok(diff)
+}
+
+/// `path` relative to `root`, joined with `/` on every platform
+fn slash_relative(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Merkle-style root over a tree: BLAKE3 of every `relative/path\0digest\n` line in path order.
+///
+/// Paths use `/` on every platform so the same tree hashes the same everywhere. Returns the root
+/// and the per-file `(path, digest)` list it was computed from, both as hex.
+fn audit_tree(dir: &Path) -> Result<(String, Vec<(String, String)>)> {
+    let entries = enumerate_directory_filtered(dir, &FileFilter::default())
+        .with_context(|| format!("Failed to enumerate {}", dir.display()))?;
+    let mut files = entries
+        .par_iter()
+        .map(|entry| {
+            let rel = slash_relative(&entry.path, dir);
+            let digest = blake3::Hash::from(hash_file(&entry.path)?).to_hex();
+            Ok((rel, digest.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    files.sort();
+
+    let mut hasher = blake3::Hasher::new();
+    for (rel, digest) in &files {
+        hasher.update(rel.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(digest.as_bytes());
+        hasher.update(b"\n");
+    }
+    Ok((hasher.finalize().to_hex().to_string(), files))
+}
+
 fn verify_local_vs_local(src: &Path, dest: &Path, checksum: bool) -> Result<VerifySummary> {
         use std::collections::{HashMap, HashSet};
-    let filter = FileFilter {
-        exclude_files: vec![],
-        exclude_dirs: vec![],
-        min_size: None,
-        max_size: None,
-    };
+    let filter = FileFilter::default();
     let left = enumerate_directory_filtered(src, &filter)?;
     let right = enumerate_directory_filtered(dest, &filter)?;
     let mut left_map: HashMap<String, &FileEntry> = HashMap::new();
@@ -1402,13 +3457,7 @@ fn verify_local_vs_local(src: &Path, dest: &Path, checksum: bool) -> Result<Veri
     for k in keys {
         match (left_map.get(&k), right_map.get(&k)) {
             (Some(l), Some(r)) => {
-                let differs = if checksum {
-                    let lh = hash_file(&l.path)?;
-                    let rh = hash_file(&r.path)?;
-                    lh != rh
-                } else {
-                    l.size != r.size
-                };
+                let differs = pair_differs(l, r, checksum, &mut hash_file)?;
                 if differs {
                     changed += 1;
                     if sample.len() < 50 {
@@ -1469,12 +3518,7 @@ fn verify_local_vs_remote(
 ) -> Result<VerifySummary> {
     use std::collections::{HashMap, HashSet};
     // Enumerate local files
-    let filter = FileFilter {
-        exclude_files: vec![],
-        exclude_dirs: vec![],
-        min_size: None,
-        max_size: None,
-    };
+    let filter = FileFilter::default();
     let left = enumerate_directory_filtered(src, &filter)?;
     let mut local_map: HashMap<String, FileEntry> = HashMap::new();
     for e in left {
@@ -1595,12 +3639,7 @@ fn verify_remote_vs_local(
         &remote_files,
         secure,
     ))?;
-    let filter = FileFilter {
-        exclude_files: vec![],
-        exclude_dirs: vec![],
-        min_size: None,
-        max_size: None,
-    };
+    let filter = FileFilter::default();
     let right = enumerate_directory_filtered(dest, &filter)?;
     let mut local_map: HashMap<String, FileEntry> = HashMap::new();
     for e in right {
@@ -1700,4 +3739,1476 @@ fn hash_file(path: &Path) -> Result<[u8; 32]> {
     let mut out = [0u8; 32];
     out.copy_from_slice(hasher.finalize().as_bytes());
     Ok(out)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path, contents: &[u8]) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Every file under `root` as an unfiltered copy job
+    fn jobs_for(root: &Path) -> Vec<CopyJob> {
+        enumerate_directory_filtered(root, &FileFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|entry| CopyJob { entry })
+            .collect()
+    }
+
+    #[test]
+    fn mirror_plan_never_schedules_destination_root() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("keep.txt"), b"keep");
+        touch(&dst.path().join("keep.txt"), b"keep");
+        touch(&dst.path().join("sub/extra.txt"), b"extra");
+
+        // Destination spelled with a trailing separator
+        let dst_slash = PathBuf::from(format!("{}/", dst.path().display()));
+        let (files, dirs) =
+            plan_mirror_deletion(src.path(), &dst_slash, &FileFilter::default()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("sub/extra.txt"));
+        for p in files.iter().chain(dirs.iter()) {
+            assert_ne!(p.as_path(), dst.path());
+            assert_ne!(p.as_path(), dst_slash.as_path());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn force_updates_read_only_destination() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("docs/a.txt"), b"new contents");
+        touch(&dst.path().join("docs/a.txt"), b"old");
+        let target = dst.path().join("docs/a.txt");
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o444)).unwrap();
+
+        let jobs = [CopyJob {
+            entry: FileEntry {
+                path: src.path().join("docs/a.txt"),
+                size: 12,
+                is_directory: false,
+            },
+        }];
+        let unlocked = unlock_destinations(jobs.iter(), src.path(), dst.path());
+        assert_eq!(unlocked.len(), 1);
+        std::fs::copy(src.path().join("docs/a.txt"), &target).unwrap();
+        relock_destinations(unlocked);
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"new contents");
+        let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o444);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relock_guard_restores_permissions_on_early_exit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"new");
+        touch(&dst.path().join("a.txt"), b"old");
+        let target = dst.path().join("a.txt");
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o444)).unwrap();
+
+        let jobs = jobs_for(src.path());
+        let failing_run = || -> Result<()> {
+            let _unlocked = Relock(unlock_destinations(jobs.iter(), src.path(), dst.path()));
+            let mode = std::fs::metadata(&target)?.permissions().mode();
+            assert_eq!(mode & 0o200, 0o200);
+            anyhow::bail!("Stopped: destination is out of space")
+        };
+        assert!(failing_run().is_err());
+        let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o444);
+    }
+
+    #[test]
+    fn dir_mtimes_restored_at_every_level() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a/b/c/f.txt"), b"f");
+        touch(&src.path().join("a/g.txt"), b"g");
+        let levels = ["", "a", "a/b", "a/b/c"];
+        for (i, level) in levels.iter().enumerate() {
+            let t = filetime::FileTime::from_unix_time(1_000_000_000 + i as i64 * 1000, 0);
+            filetime::set_file_mtime(src.path().join(level), t).unwrap();
+        }
+
+        let jobs = jobs_for(src.path());
+        for job in &jobs {
+            let dst_file = compute_destination(&job.entry.path, src.path(), dst.path());
+            std::fs::create_dir_all(dst_file.parent().unwrap()).unwrap();
+            std::fs::copy(&job.entry.path, &dst_file).unwrap();
+        }
+        let dirs = ancestor_dirs(jobs.iter(), src.path());
+        assert_eq!(dirs.len(), 4);
+        restore_dir_mtimes(&dirs, src.path(), dst.path());
+
+        let mtime = |p: PathBuf| {
+            filetime::FileTime::from_last_modification_time(&std::fs::metadata(p).unwrap())
+        };
+        for level in levels {
+            assert_eq!(
+                mtime(dst.path().join(level)),
+                mtime(src.path().join(level)),
+                "{}",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn mirror_with_no_delete_keeps_extras() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("new.txt"), b"n");
+        touch(&dst.path().join("extra.txt"), b"x");
+
+        let (s, d) = (src.path().to_str().unwrap(), dst.path().to_str().unwrap());
+        run(Args::parse_from(["blit", "--no-delete", "mirror", s, d])).unwrap();
+        assert!(dst.path().join("new.txt").exists());
+        assert!(dst.path().join("extra.txt").exists());
+
+        run(Args::parse_from(["blit", "mirror", s, d])).unwrap();
+        assert!(!dst.path().join("extra.txt").exists());
+    }
+
+    #[test]
+    fn mirror_subcommand_applies_filters_and_purges() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("keep.txt"), b"k");
+        touch(&src.path().join("scratch.tmp"), b"t");
+        touch(&src.path().join("cache/blob"), b"c");
+        touch(&dst.path().join("extra.txt"), b"x");
+
+        let (s, d) = (src.path().to_str().unwrap(), dst.path().to_str().unwrap());
+        run(Args::parse_from([
+            "blit", "--xf", "*.tmp", "--xd", "cache", "mirror", s, d,
+        ]))
+        .unwrap();
+        assert!(dst.path().join("keep.txt").exists());
+        assert!(!dst.path().join("scratch.tmp").exists());
+        assert!(!dst.path().join("cache").exists());
+        assert!(!dst.path().join("extra.txt").exists());
+    }
+
+    #[test]
+    fn list_extras_matches_mirror_deletions() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("old/extra.bin"), b"12345");
+        touch(&dst.path().join("stale.txt"), b"s");
+
+        let extras = list_extras(src.path(), dst.path(), &FileFilter::default()).unwrap();
+        assert_eq!(
+            extras,
+            vec![
+                (dst.path().join("old/extra.bin"), 5),
+                (dst.path().join("stale.txt"), 1)
+            ]
+        );
+        // Listing is read-only
+        assert!(dst.path().join("stale.txt").exists());
+
+        handle_mirror_deletion(
+            src.path(),
+            dst.path(),
+            &FileFilter::default(),
+            false,
+            false,
+            false,
+            &PurgeLimits::default(),
+        )
+        .unwrap();
+        for (path, _) in &extras {
+            assert!(!path.exists());
+        }
+        assert!(dst.path().join("kept.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn broken_symlink_policies() {
+        let src = tempfile::tempdir().unwrap();
+        touch(&src.path().join("real.txt"), b"r");
+        std::os::unix::fs::symlink("gone.txt", src.path().join("dangling")).unwrap();
+        let copy_with = |policy: &str| {
+            let dst = tempfile::tempdir().unwrap();
+            let args = Args::parse_from([
+                "blit",
+                "--broken-symlinks",
+                policy,
+                src.path().to_str().unwrap(),
+                dst.path().to_str().unwrap(),
+            ]);
+            let result = run(args);
+            (dst, result)
+        };
+
+        let (dst, result) = copy_with("keep");
+        result.unwrap();
+        let link = dst.path().join("dangling");
+        assert_eq!(
+            std::fs::read_link(&link).unwrap(),
+            PathBuf::from("gone.txt")
+        );
+        assert!(dst.path().join("real.txt").exists());
+
+        let (dst, result) = copy_with("skip");
+        result.unwrap();
+        assert!(std::fs::symlink_metadata(dst.path().join("dangling")).is_err());
+        assert!(dst.path().join("real.txt").exists());
+
+        let (dst, result) = copy_with("error");
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("broken symlinks"), "{}", err);
+        assert!(!dst.path().join("real.txt").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn fsync_dirs_run_completes_with_files_in_place() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("top.txt"), b"t");
+        touch(&src.path().join("a/b/deep.txt"), b"d");
+        let args = Args::parse_from([
+            "blit",
+            "--fsync-dirs",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]);
+        run(args).unwrap();
+        assert!(dst.path().join("top.txt").exists());
+        assert_eq!(
+            std::fs::read(dst.path().join("a/b/deep.txt")).unwrap(),
+            b"d"
+        );
+        sync_dir(&dst.path().join("a/b")).unwrap();
+    }
+
+    #[test]
+    fn link_candidates_use_the_skip_unchanged_slack() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (src, prev) = (tmp.path().join("src.txt"), tmp.path().join("prev.txt"));
+        touch(&src, b"same");
+        touch(&prev, b"same");
+        let t = filetime::FileTime::from_unix_time;
+        filetime::set_file_mtime(&src, t(1_600_000_001, 500_000_000)).unwrap();
+        filetime::set_file_mtime(&prev, t(1_600_000_000, 0)).unwrap();
+
+        // On a nanosecond destination a second and a half newer is a change, not a match
+        let (exact, fat) = (std::time::Duration::ZERO, std::time::Duration::from_secs(2));
+        assert!(!link_candidate_matches(&src, &prev, None, exact));
+        assert!(link_candidate_matches(&src, &prev, None, fat));
+        assert!(!link_candidate_matches(&src, tmp.path(), None, fat));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn link_dest_hard_links_unchanged_files() {
+        use std::os::unix::fs::MetadataExt;
+
+        let src = tempfile::tempdir().unwrap();
+        let prev = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("same/a.txt"), b"same");
+        touch(&src.path().join("changed.txt"), b"new");
+        touch(&prev.path().join("same/a.txt"), b"same");
+        touch(&prev.path().join("changed.txt"), b"older");
+        let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+        for p in [src.path(), prev.path()] {
+            filetime::set_file_mtime(p.join("same/a.txt"), mtime).unwrap();
+        }
+
+        let args = Args::parse_from([
+            "blit",
+            "--link-dest",
+            prev.path().to_str().unwrap(),
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]);
+        run(args).unwrap();
+
+        let ino = |p: PathBuf| std::fs::metadata(p).unwrap().ino();
+        assert_eq!(
+            ino(dst.path().join("same/a.txt")),
+            ino(prev.path().join("same/a.txt"))
+        );
+        assert_ne!(
+            ino(dst.path().join("changed.txt")),
+            ino(prev.path().join("changed.txt"))
+        );
+        assert_eq!(
+            std::fs::read(dst.path().join("changed.txt")).unwrap(),
+            b"new"
+        );
+    }
+
+    #[test]
+    fn flatten_maps_files_to_dest_root_and_resolves_collisions() {
+        let src = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a/report.txt"), b"first");
+        touch(&src.path().join("b/c/report.txt"), b"second");
+        touch(&src.path().join("b/unique.bin"), b"u");
+        let jobs = || jobs_for(src.path());
+        let dst = Path::new("/flat");
+        let names = |pairs: &[(FileEntry, PathBuf)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(e, d)| {
+                    let src_rel = e.path.strip_prefix(src.path()).unwrap();
+                    let dst_rel = d.strip_prefix(dst).unwrap();
+                    (src_rel.display().to_string(), dst_rel.display().to_string())
+                })
+                .collect()
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        let (pairs, collisions) = flatten_pairs(jobs(), dst, OnCollision::Rename);
+        assert_eq!(collisions, 1);
+        assert_eq!(
+            names(&pairs),
+            vec![
+                pair("a/report.txt", "report.txt"),
+                pair("b/c/report.txt", "report-1.txt"),
+                pair("b/unique.bin", "unique.bin"),
+            ]
+        );
+
+        let (pairs, _) = flatten_pairs(jobs(), dst, OnCollision::Skip);
+        assert_eq!(
+            names(&pairs),
+            vec![
+                pair("a/report.txt", "report.txt"),
+                pair("b/unique.bin", "unique.bin"),
+            ]
+        );
+
+        let (pairs, _) = flatten_pairs(jobs(), dst, OnCollision::Overwrite);
+        assert_eq!(
+            names(&pairs),
+            vec![
+                pair("b/c/report.txt", "report.txt"),
+                pair("b/unique.bin", "unique.bin"),
+            ]
+        );
+    }
+
+    #[test]
+    fn flatten_run_copies_into_one_directory() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("x/one.txt"), b"1");
+        touch(&src.path().join("y/z/two.txt"), b"2");
+        let args = Args::parse_from([
+            "blit",
+            "--flatten",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]);
+        run(args).unwrap();
+
+        let mut names: Vec<String> = std::fs::read_dir(dst.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["one.txt", "two.txt"]);
+        assert_eq!(std::fs::read(dst.path().join("two.txt")).unwrap(), b"2");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_subdirectory_is_skipped_unless_strict_scan() {
+        use std::os::unix::fs::PermissionsExt;
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("ok/a.txt"), b"a");
+        touch(&src.path().join("locked/b.txt"), b"b");
+        let locked = src.path().join("locked");
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        let denied = std::fs::read_dir(&locked).is_err();
+        let sync = |extra: &[&str]| {
+            let mut argv = vec!["blit"];
+            argv.extend_from_slice(extra);
+            argv.extend([src.path().to_str().unwrap(), dst.path().to_str().unwrap()]);
+            run(Args::parse_from(argv))
+        };
+
+        let strict = sync(&["--strict-scan"]);
+        let lenient = sync(&[]);
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        // Root reads through mode 000, so there is nothing to skip
+        if !denied {
+            return;
+        }
+        assert!(strict.is_err());
+        lenient.unwrap();
+        assert!(dst.path().join("ok/a.txt").exists());
+        assert!(!dst.path().join("locked/b.txt").exists());
+    }
+
+    #[test]
+    fn order_sorts_jobs_before_scheduling() {
+        let job = |name: &str, size: u64| CopyJob {
+            entry: FileEntry {
+                path: PathBuf::from(name),
+                size,
+                is_directory: false,
+            },
+        };
+        let ordered = |order: &str| {
+            let args = Args::parse_from(["blit", "--order", order, "a", "b"]);
+            let mut jobs = vec![job("b", 10), job("c", 300), job("a", 20), job("d", 10)];
+            order_jobs(&mut jobs, args.order);
+            jobs.iter()
+                .map(|j| j.entry.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ordered("path"), ["a", "b", "c", "d"]);
+        assert_eq!(ordered("largest"), ["c", "a", "b", "d"]);
+        assert_eq!(ordered("smallest"), ["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn large_file_concurrency_caps_copies_in_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let concurrency = Args::parse_from(["blit", "--large-file-concurrency", "3", "a", "b"])
+            .large_file_concurrency;
+        let slots = large_copy_slots(concurrency);
+        let (in_flight, peak) = (AtomicUsize::new(0), AtomicUsize::new(0));
+
+        (0..48).into_par_iter().for_each(|_| {
+            let _slot = slots.acquire();
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        let default = Args::parse_from(["blit", "a", "b"]);
+        assert_eq!(default.large_file_concurrency, 2);
+    }
+
+    #[test]
+    fn delay_updates_leaves_destination_untouched_on_failure() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"new");
+        touch(&src.path().join("sub/b.txt"), b"added");
+        touch(&dst.path().join("a.txt"), b"old");
+        // A sparse large file trips --min-free, failing the run after the small files are staged
+        let big = std::fs::File::create(src.path().join("big.bin")).unwrap();
+        big.set_len(200 << 20).unwrap();
+        drop(big);
+        let sync = |extra: &[&str]| {
+            let mut argv = vec!["blit", "--delay-updates"];
+            argv.extend_from_slice(extra);
+            argv.extend([src.path().to_str().unwrap(), dst.path().to_str().unwrap()]);
+            run(Args::parse_from(argv))
+        };
+
+        assert!(sync(&["--min-free", "1000000T"]).is_err());
+        assert_eq!(std::fs::read(dst.path().join("a.txt")).unwrap(), b"old");
+        assert!(!dst.path().join("sub/b.txt").exists());
+        assert!(!dst.path().join(STAGING_DIR).exists());
+
+        std::fs::remove_file(src.path().join("big.bin")).unwrap();
+        sync(&[]).unwrap();
+        assert_eq!(std::fs::read(dst.path().join("a.txt")).unwrap(), b"new");
+        let added = dst.path().join("sub/b.txt");
+        assert_eq!(std::fs::read(added).unwrap(), b"added");
+        assert!(!dst.path().join(STAGING_DIR).exists());
+    }
+
+    #[test]
+    fn delay_updates_only_clears_its_own_staging_dir() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"a");
+        touch(&src.path().join(STAGING_DIR).join("src.txt"), b"s");
+        let foreign = dst.path().join(STAGING_DIR).join("mine.txt");
+        touch(&foreign, b"keep");
+        let sync = |extra: &[&str]| {
+            let mut argv = vec!["blit", "--delay-updates"];
+            argv.extend_from_slice(extra);
+            argv.extend([src.path().to_str().unwrap(), dst.path().to_str().unwrap()]);
+            run(Args::parse_from(argv))
+        };
+
+        let err = sync(&[]).unwrap_err();
+        assert!(err.to_string().contains("not created by blit"), "{}", err);
+        assert_eq!(std::fs::read(&foreign).unwrap(), b"keep");
+        assert!(!dst.path().join("a.txt").exists());
+
+        // Mirroring neither copies a source staging dir nor purges the destination one
+        run(Args::parse_from([
+            "blit",
+            "--mir",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(std::fs::read(&foreign).unwrap(), b"keep");
+        assert!(!dst.path().join(STAGING_DIR).join("src.txt").exists());
+
+        // A leftover blit marked as its own is cleared and never committed
+        std::fs::remove_dir_all(dst.path().join(STAGING_DIR)).unwrap();
+        let tree = prepare_staging(dst.path()).unwrap();
+        touch(&tree.join("stale.txt"), b"stale");
+        sync(&[]).unwrap();
+        assert!(!dst.path().join("stale.txt").exists());
+        assert!(!dst.path().join(STAGING_DIR).exists());
+    }
+
+    #[test]
+    fn missing_source_fails_before_touching_destination() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = tmp.path().join("no/such/src");
+        let dst = tmp.path().join("dst");
+        let args = Args::parse_from(["blit", missing.to_str().unwrap(), dst.to_str().unwrap()]);
+        let err = run(args).unwrap_err();
+        assert!(
+            err.to_string().starts_with("Source does not exist"),
+            "{}",
+            err
+        );
+        assert!(!dst.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn metadata_only_fixes_matching_files_without_copying() {
+        use std::os::unix::fs::PermissionsExt;
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("same.txt"), b"same");
+        touch(&dst.path().join("same.txt"), b"same");
+        touch(&src.path().join("changed.txt"), b"new data");
+        touch(&dst.path().join("changed.txt"), b"old");
+        touch(&src.path().join("missing.txt"), b"m");
+        let old = filetime::FileTime::from_unix_time(1_500_000_000, 0);
+        filetime::set_file_mtime(src.path().join("same.txt"), old).unwrap();
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+        std::fs::set_permissions(
+            src.path().join("same.txt"),
+            std::fs::Permissions::from_mode(0o640),
+        )
+        .unwrap();
+        std::fs::set_permissions(
+            dst.path().join("same.txt"),
+            std::fs::Permissions::from_mode(0o600),
+        )
+        .unwrap();
+
+        let args = Args::parse_from([
+            "blit",
+            "--metadata-only",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]);
+        run(args).unwrap();
+
+        let fixed = dst.path().join("same.txt");
+        let md = std::fs::metadata(&fixed).unwrap();
+        assert_eq!(filetime::FileTime::from_last_modification_time(&md), old);
+        assert_eq!(mode(&fixed), 0o640);
+        // No data moves: different files keep their content, missing ones stay missing
+        let changed = dst.path().join("changed.txt");
+        assert_eq!(std::fs::read(changed).unwrap(), b"old");
+        assert!(!dst.path().join("missing.txt").exists());
+    }
+
+    #[test]
+    fn overlong_destination_paths_are_skipped() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        let long_name = "x".repeat(40);
+        touch(&src.path().join("short.txt"), b"s");
+        touch(&src.path().join(&long_name), b"l");
+        let jobs = jobs_for(src.path());
+
+        let fitting = dst.path().join("short.txt");
+        let max = fitting.to_string_lossy().chars().count();
+        let (fits, too_long) = split_overlong_paths(jobs, src.path(), dst.path(), max);
+        assert_eq!(fits.len(), 1);
+        assert!(fits[0].entry.path.ends_with("short.txt"));
+        assert_eq!(too_long, vec![dst.path().join(&long_name)]);
+
+        let args = Args::parse_from([
+            "blit",
+            "--max-path-len",
+            &max.to_string(),
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+        ]);
+        run(args).unwrap();
+        assert!(dst.path().join("short.txt").exists());
+        assert!(!dst.path().join(&long_name).exists());
+    }
+
+    #[test]
+    fn case_collisions_are_reported_only_for_case_insensitive_destinations() {
+        let src = tempfile::tempdir().unwrap();
+        touch(&src.path().join("README"), b"upper");
+        touch(&src.path().join("readme"), b"lower");
+        touch(&src.path().join("other.txt"), b"o");
+        let jobs = jobs_for(src.path());
+
+        assert!(case_collision_warnings(&jobs, src.path(), false).is_empty());
+        let warnings = case_collision_warnings(&jobs, src.path(), true);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("differ only in case"));
+
+        // The temp destination is case-sensitive, so --strict-case has nothing to refuse
+        #[cfg(target_os = "linux")]
+        {
+            let dst = tempfile::tempdir().unwrap();
+            let args = Args::parse_from([
+                "blit",
+                "--strict-case",
+                src.path().to_str().unwrap(),
+                dst.path().to_str().unwrap(),
+            ]);
+            run(args).unwrap();
+            assert_eq!(std::fs::read(dst.path().join("README")).unwrap(), b"upper");
+            assert_eq!(std::fs::read(dst.path().join("readme")).unwrap(), b"lower");
+        }
+    }
+
+    #[test]
+    fn audit_root_matches_for_identical_trees_only() {
+        let build = || {
+            let dir = tempfile::tempdir().unwrap();
+            touch(&dir.path().join("a.txt"), b"alpha");
+            touch(&dir.path().join("sub/b.bin"), b"beta");
+            dir
+        };
+        let (left, right) = (build(), build());
+        let (root_left, files) = audit_tree(left.path()).unwrap();
+        let (root_right, _) = audit_tree(right.path()).unwrap();
+        assert_eq!(root_left, root_right);
+        assert_eq!(
+            files.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+            vec!["a.txt", "sub/b.bin"]
+        );
+
+        touch(&right.path().join("sub/b.bin"), b"BETA");
+        assert_ne!(audit_tree(right.path()).unwrap().0, root_left);
+
+        // Same content under a different name is a different tree
+        let renamed = build();
+        std::fs::rename(renamed.path().join("a.txt"), renamed.path().join("c.txt")).unwrap();
+        assert_ne!(audit_tree(renamed.path()).unwrap().0, root_left);
+    }
+
+    #[test]
+    fn max_delete_suppresses_deletions_but_still_copies() {
+        let src = tempfile::tempdir().unwrap();
+        touch(&src.path().join("new.txt"), b"n");
+        let mirror_with = |max: &str| {
+            let dst = tempfile::tempdir().unwrap();
+            touch(&dst.path().join("a.txt"), b"a");
+            touch(&dst.path().join("b.txt"), b"b");
+            let args = Args::parse_from([
+                "blit",
+                "--mir",
+                "--max-delete",
+                max,
+                src.path().to_str().unwrap(),
+                dst.path().to_str().unwrap(),
+            ]);
+            let result = run(args);
+            (dst, result)
+        };
+
+        let (dst, result) = mirror_with("1");
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("--max-delete 1"), "{}", err);
+        assert!(dst.path().join("new.txt").exists());
+        assert!(dst.path().join("a.txt").exists());
+        assert!(dst.path().join("b.txt").exists());
+
+        let (dst, result) = mirror_with("2");
+        result.unwrap();
+        assert!(dst.path().join("new.txt").exists());
+        assert!(!dst.path().join("a.txt").exists());
+        assert!(!dst.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn keep_dest_dotdirs_protects_destination_only_hidden_dirs() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"a");
+        touch(&src.path().join(".config/kept"), b"k");
+        touch(&dst.path().join(".git/HEAD"), b"ref");
+        touch(&dst.path().join(".config/stale"), b"s");
+        touch(&dst.path().join(".hidden_file"), b"h");
+        touch(&dst.path().join("sub/.cache/x"), b"x");
+
+        let filter = FileFilter {
+            keep_dest_dotdirs: true,
+            ..Default::default()
+        };
+        let (mut files, _) = plan_mirror_deletion(src.path(), dst.path(), &filter).unwrap();
+        files.sort();
+        // Only .git is destination-only; .config is managed because the source has it
+        assert_eq!(
+            files,
+            vec![
+                dst.path().join(".config/stale"),
+                dst.path().join(".hidden_file"),
+                dst.path().join("sub/.cache/x"),
+            ]
+        );
+
+        let (files, _) =
+            plan_mirror_deletion(src.path(), dst.path(), &FileFilter::default()).unwrap();
+        assert!(files.contains(&dst.path().join(".git/HEAD")));
+    }
+
+    #[test]
+    fn max_purge_aborts_before_any_deletion() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("a.txt"), b"a");
+        touch(&dst.path().join("b.txt"), b"b");
+        let purge = |limits: PurgeLimits| {
+            handle_mirror_deletion(
+                src.path(),
+                dst.path(),
+                &FileFilter::default(),
+                false,
+                false,
+                false,
+                &limits,
+            )
+        };
+
+        let over_count = PurgeLimits {
+            max_files: Some(1),
+            ..Default::default()
+        };
+        let err = purge(over_count).unwrap_err();
+        assert!(err.to_string().contains("--max-purge 1"));
+        // 2 of 3 destination files is ~67%
+        let over_pct = PurgeLimits {
+            max_pct: Some(50.0),
+            ..Default::default()
+        };
+        assert!(purge(over_pct).is_err());
+        assert!(dst.path().join("a.txt").exists());
+        assert!(dst.path().join("b.txt").exists());
+
+        let within = PurgeLimits {
+            max_files: Some(2),
+            max_pct: Some(70.0),
+            ..Default::default()
+        };
+        assert_eq!(purge(within).unwrap().files, 2);
+        assert!(!dst.path().join("a.txt").exists());
+        assert!(dst.path().join("kept.txt").exists());
+    }
+
+    #[test]
+    fn max_purge_pct_counts_destination_files_not_directories() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        for dir in ["d1", "d2", "d3"] {
+            std::fs::create_dir_all(src.path().join(dir)).unwrap();
+            std::fs::create_dir_all(dst.path().join(dir)).unwrap();
+        }
+        touch(&src.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("kept.txt"), b"k");
+        touch(&dst.path().join("extra.txt"), b"x");
+        let purge = |pct: f64| {
+            let limits = PurgeLimits {
+                max_pct: Some(pct),
+                ..Default::default()
+            };
+            handle_mirror_deletion(
+                src.path(),
+                dst.path(),
+                &FileFilter::default(),
+                false,
+                false,
+                false,
+                &limits,
+            )
+        };
+
+        // 1 of 2 files is 50%, though only 20% of the 5 entries
+        let err = purge(40.0).unwrap_err();
+        assert!(
+            err.to_string().contains("1 of 2 destination files"),
+            "{}",
+            err
+        );
+        assert!(dst.path().join("extra.txt").exists());
+        assert_eq!(purge(50.0).unwrap().files, 1);
+    }
+
+    #[test]
+    fn prune_removes_dirs_emptied_by_mirror_delete() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("kept/a.txt"), b"a");
+        std::fs::create_dir_all(src.path().join("empty_in_src")).unwrap();
+        touch(&dst.path().join("kept/a.txt"), b"a");
+        touch(&dst.path().join("gone/deeper/extra.txt"), b"x");
+        std::fs::create_dir_all(dst.path().join("empty_in_src")).unwrap();
+
+        let deleted = handle_mirror_deletion(
+            src.path(),
+            dst.path(),
+            &FileFilter::default(),
+            false,
+            false,
+            false,
+            &PurgeLimits::default(),
+        )
+        .unwrap();
+        assert!(dst.path().join("gone/deeper").is_dir());
+        let mut parents = deleted.parents;
+        assert_eq!(parents, [dst.path().join("gone/deeper")].into());
+
+        let pruned = prune_empty_dirs(
+            src.path(),
+            dst.path(),
+            &FileFilter::default(),
+            false,
+            &mut parents,
+        );
+        assert_eq!(pruned.unwrap(), 2);
+        // Everything that lost an entry, whether or not it survived
+        assert_eq!(
+            parents,
+            [
+                dst.path().to_path_buf(),
+                dst.path().join("gone"),
+                dst.path().join("gone/deeper")
+            ]
+            .into()
+        );
+        assert!(!dst.path().join("gone").exists());
+        assert!(dst.path().join("kept/a.txt").exists());
+        assert!(dst.path().join("empty_in_src").is_dir());
+    }
+
+    #[test]
+    fn prune_keeps_dirs_mirror_deletion_leaves_alone() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        for dir in ["cache", "sub/cache", ".snapshots", "gone"] {
+            std::fs::create_dir_all(dst.path().join(dir)).unwrap();
+        }
+        let filter = FileFilter {
+            exclude_dirs: vec!["cache".into()],
+            keep_dest_dotdirs: true,
+            ..Default::default()
+        };
+
+        let pruned = prune_empty_dirs(
+            src.path(),
+            dst.path(),
+            &filter,
+            false,
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(pruned, 1);
+        assert!(!dst.path().join("gone").exists());
+        assert!(dst.path().join("cache").is_dir());
+        assert!(dst.path().join("sub/cache").is_dir());
+        assert!(dst.path().join(".snapshots").is_dir());
+    }
+
+    #[test]
+    fn thread_count_accepts_percentages() {
+        assert_eq!(parse_thread_count_with("50%", 16), Ok(8));
+        assert_eq!(parse_thread_count_with("100%", 16), Ok(16));
+        assert_eq!(parse_thread_count_with("1%", 16), Ok(1));
+        assert_eq!(parse_thread_count_with("33%", 8), Ok(3));
+        // Over-cap percentages clamp to the available cores
+        assert_eq!(parse_thread_count_with("250%", 16), Ok(16));
+        assert!(parse_thread_count_with("0%", 16).is_err());
+        assert!(parse_thread_count_with("half%", 16).is_err());
+    }
+
+    #[test]
+    fn sizes_accept_binary_suffixes() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("64k"), Ok(64 * 1024));
+        assert_eq!(parse_size("2G"), Ok(2 * 1024 * 1024 * 1024));
+        assert!(parse_size("5X").is_err());
+        assert!(parse_size("G").is_err());
+    }
+
+    #[test]
+    fn io_buffer_is_range_checked() {
+        assert_eq!(parse_io_buffer("256K"), Ok(256 * 1024));
+        assert_eq!(parse_io_buffer("4k"), Ok(4096));
+        assert!(parse_io_buffer("1K").is_err());
+        assert!(parse_io_buffer("1G").is_err());
+        let args = Args::parse_from(["blit", "--io-buffer", "8M", "a", "b"]);
+        assert_eq!(
+            buffer_sizer_for(&args).calculate_buffer_size(1, false),
+            8 << 20
+        );
+    }
+
+    #[test]
+    fn env_vars_supply_defaults_that_flags_override() {
+        use clap::CommandFactory;
+
+        // Checks the definitions rather than calling set_var: tests run in parallel, and every
+        // Args::parse_from in them reads the process environment
+        let cmd = Args::command();
+        let arg = |id: &str| cmd.get_arguments().find(|a| a.get_id() == id).unwrap();
+        for (id, var) in [
+            ("threads", "BLIT_THREADS"),
+            ("max_open_files", "BLIT_MAX_OPEN_FILES"),
+            ("net_workers", "BLIT_NET_WORKERS"),
+            ("log_file", "BLIT_LOG_FILE"),
+        ] {
+            assert_eq!(arg(id).get_env(), Some(std::ffi::OsStr::new(var)));
+        }
+        assert_eq!(arg("net_workers").get_default_values(), ["4"]);
+        // Whatever the environment holds, an explicit flag wins
+        let from_flag = Args::parse_from(["blit", "--net-workers", "2", "a", "b"]);
+        assert_eq!(from_flag.net_workers, 2);
+    }
+
+    #[test]
+    fn thread_count_keeps_absolute_values() {
+        assert_eq!(parse_thread_count_with("0", 16), Ok(0));
+        assert_eq!(parse_thread_count_with("12", 4), Ok(12));
+        assert!(parse_thread_count_with("-1", 4).is_err());
+    }
+
+    #[test]
+    fn unchanged_files_are_reported_as_skipped() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("same.txt"), b"same");
+        touch(&dst.path().join("same.txt"), b"same");
+        touch(&src.path().join("new.txt"), b"new");
+
+        let jobs = jobs_for(src.path());
+        let no_slack = std::time::Duration::ZERO;
+        let (copy, skipped) = partition_unchanged(jobs, src.path(), dst.path(), None, no_slack);
+        assert_eq!(copy.len(), 1);
+        assert!(copy[0].entry.path.ends_with("new.txt"));
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].entry.path.ends_with("same.txt"));
+
+        let stats = CopyStats {
+            files_skipped: skipped.len() as u64,
+            elapsed: std::time::Duration::from_secs(1),
+            ..Default::default()
+        };
+        let lines = summary_lines(&stats);
+        assert!(lines.contains(&"Files skipped (same): 1".to_string()));
+    }
+
+    #[test]
+    fn progress_interval_sets_refresh_rate() {
+        let hz = |flag: &[&str]| {
+            let mut argv = vec!["blit"];
+            argv.extend_from_slice(flag);
+            argv.extend(["a", "b"]);
+            let ms = Args::parse_from(argv).progress_interval;
+            progress_refresh_hz(std::time::Duration::from_millis(ms))
+        };
+        assert_eq!(hz(&[]), 4);
+        assert_eq!(hz(&["--progress-interval", "500"]), 2);
+        assert_eq!(hz(&["--progress-interval", "10"]), 100);
+        // Slower than once a second still redraws at indicatif's 1 Hz minimum
+        assert_eq!(hz(&["--progress-interval", "5000"]), 1);
+        assert!(Args::try_parse_from(["blit", "--progress-interval", "0", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn summary_survives_progress_but_not_no_summary() {
+        let stats = CopyStats::default();
+        let summary = |argv: &[&str]| end_of_run_summary(&Args::parse_from(argv), &stats);
+
+        assert!(!summary(&["blit", "a", "b"]).is_empty());
+        assert!(!summary(&["blit", "-p", "a", "b"]).is_empty());
+        assert!(summary(&["blit", "-p", "--no-summary", "a", "b"]).is_empty());
+    }
+
+    #[test]
+    fn checksum_skip_corrects_stale_mtime() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("same.txt"), b"identical");
+        touch(&dst.path().join("same.txt"), b"identical");
+        let src_time = filetime::FileTime::from_unix_time(2_000_000_000, 0);
+        filetime::set_file_mtime(src.path().join("same.txt"), src_time).unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(dst.path().join("same.txt"), old).unwrap();
+
+        let jobs = jobs_for(src.path());
+        let no_slack = std::time::Duration::ZERO;
+        let (copy, skipped) = partition_unchanged(jobs, src.path(), dst.path(), Some(3), no_slack);
+        assert!(copy.is_empty());
+        assert_eq!(correct_skipped_mtimes(&skipped, src.path(), dst.path()), 1);
+
+        let md = std::fs::metadata(dst.path().join("same.txt")).unwrap();
+        assert_eq!(
+            filetime::FileTime::from_last_modification_time(&md),
+            src_time
+        );
+        // Already in sync: nothing left to fix
+        assert_eq!(correct_skipped_mtimes(&skipped, src.path(), dst.path()), 0);
+    }
+
+    #[test]
+    fn second_mirror_run_copies_nothing() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        for i in 0..300 {
+            touch(&src.path().join(format!("d{}/f{}.txt", i % 7, i)), b"data");
+        }
+        // Sub-second source mtimes, which the tar path's whole-second headers cannot carry
+        let t = filetime::FileTime::from_unix_time(1_600_000_000, 343_852_207);
+        for job in jobs_for(src.path()) {
+            filetime::set_file_mtime(&job.entry.path, t).unwrap();
+        }
+        let (s, d) = (src.path().to_str().unwrap(), dst.path().to_str().unwrap());
+        run(Args::parse_from(["blit", "--mir", s, d])).unwrap();
+
+        // Same size and mtime as the first run left, different bytes: only a recopy would put
+        // "data" back
+        for job in jobs_for(dst.path()) {
+            let md = std::fs::metadata(&job.entry.path).unwrap();
+            std::fs::write(&job.entry.path, b"kept").unwrap();
+            let mtime = filetime::FileTime::from_last_modification_time(&md);
+            filetime::set_file_mtime(&job.entry.path, mtime).unwrap();
+        }
+        run(Args::parse_from(["blit", "--mir", s, d])).unwrap();
+        let recopied = jobs_for(dst.path())
+            .iter()
+            .filter(|job| std::fs::read(&job.entry.path).unwrap() != b"kept")
+            .count();
+        assert_eq!(recopied, 0);
+    }
+
+    #[test]
+    fn move_removes_source_only_after_a_real_copy() {
+        let root = tempfile::tempdir().unwrap();
+        let (src, dst) = (root.path().join("src"), root.path().join("dst"));
+        touch(&src.join("sub/a.txt"), b"a");
+
+        let dry = Args::parse_from(["blit", "--dry-run"]);
+        assert_eq!(move_tree(&src, &dst, &dry).unwrap(), RunOutcome::NotCopied);
+        assert!(src.join("sub/a.txt").exists());
+        assert!(!dst.join("sub/a.txt").exists());
+
+        let single = src.join("sub/a.txt");
+        let single_dst = root.path().join("one.txt");
+        assert_eq!(
+            move_tree(&single, &single_dst, &dry).unwrap(),
+            RunOutcome::NotCopied
+        );
+        assert!(single.exists() && !single_dst.exists());
+
+        let args = Args::parse_from(["blit"]);
+        assert_eq!(move_tree(&src, &dst, &args).unwrap(), RunOutcome::Copied);
+        assert!(!src.exists());
+        assert_eq!(std::fs::read(dst.join("sub/a.txt")).unwrap(), b"a");
+    }
+
+    #[test]
+    fn move_keeps_source_files_the_run_left_out() {
+        let root = tempfile::tempdir().unwrap();
+        let (src, dst) = (root.path().join("src"), root.path().join("dst"));
+        touch(&src.join("a.txt"), b"a");
+        touch(&src.join("sub/important.log"), b"keep me");
+        touch(&src.join("sub/b.txt"), b"b");
+        touch(&src.join("cache/blob"), b"c");
+
+        let args = Args::parse_from(["blit", "--xf", "*.log", "--xd", "cache"]);
+        assert_eq!(move_tree(&src, &dst, &args).unwrap(), RunOutcome::Copied);
+        let log = std::fs::read(src.join("sub/important.log")).unwrap();
+        assert_eq!(log, b"keep me");
+        assert!(src.join("cache/blob").exists());
+        assert!(!src.join("a.txt").exists() && !src.join("sub/b.txt").exists());
+        assert!(dst.join("sub/b.txt").exists() && !dst.join("sub/important.log").exists());
+    }
+
+    #[test]
+    fn move_rejects_listing_flags() {
+        let args = Args::parse_from(["blit", "--list-extras", "move", "a", "b"]);
+        assert!(check_move_flags(&args).is_err());
+        let args = Args::parse_from(["blit", "--metadata-only", "move", "a", "b"]);
+        assert!(check_move_flags(&args).is_err());
+        assert!(check_move_flags(&Args::parse_from(["blit", "move", "a", "b"])).is_ok());
+    }
+
+    #[test]
+    fn pinned_pool_builds_and_sync_completes() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .start_handler(cpu_pinner())
+            .build()
+            .unwrap();
+        #[cfg(target_os = "linux")]
+        {
+            let cpus = allowed_cpus();
+            assert!(!cpus.is_empty());
+            let current = pool.broadcast(|_| unsafe { libc::sched_getcpu() } as usize);
+            assert!(current.iter().all(|cpu| cpus.contains(cpu)));
+        }
+        drop(pool);
+
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"a");
+        touch(&src.path().join("sub/b.txt"), b"b");
+        let (s, d) = (src.path().to_str().unwrap(), dst.path().to_str().unwrap());
+        run(Args::parse_from(["blit", "--pin-threads", s, d])).unwrap();
+        assert_eq!(std::fs::read(dst.path().join("sub/b.txt")).unwrap(), b"b");
+    }
+
+    #[test]
+    fn diff_trees_categorizes_differences() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        touch(&a.path().join("same.txt"), b"same");
+        touch(&b.path().join("same.txt"), b"same");
+        touch(&a.path().join("sub/resized.txt"), b"short");
+        touch(&b.path().join("sub/resized.txt"), b"longer text");
+        touch(&a.path().join("edited.txt"), b"aaaa");
+        touch(&b.path().join("edited.txt"), b"bbbb");
+        touch(&a.path().join("sub/gone.txt"), b"a");
+        touch(&b.path().join("added.txt"), b"b");
+
+        let diff = diff_trees(a.path(), b.path(), false).unwrap();
+        assert_eq!(diff.only_a, ["sub/gone.txt"]);
+        assert_eq!(diff.only_b, ["added.txt"]);
+        // Same size, so only a content comparison catches edited.txt
+        assert_eq!(diff.changed, ["sub/resized.txt"]);
+
+        let diff = diff_trees(a.path(), b.path(), true).unwrap();
+        assert_eq!(diff.changed, ["edited.txt", "sub/resized.txt"]);
+        assert_eq!(
+            diff.lines(),
+            [
+                "> added.txt",
+                "M edited.txt",
+                "< sub/gone.txt",
+                "M sub/resized.txt"
+            ]
+        );
+        assert!(diff_trees(a.path(), a.path(), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn counts_only_genuinely_new_directories() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("same/f.txt"), b"x");
+        touch(&src.path().join("old/f.txt"), b"x");
+        touch(&src.path().join("old/new/f.txt"), b"x");
+        touch(&src.path().join("fresh/deep/f.txt"), b"x");
+        touch(&src.path().join("top.txt"), b"x");
+        // same/ only holds a file the mirror run skips
+        touch(&dst.path().join("same/f.txt"), b"x");
+        std::fs::create_dir_all(dst.path().join("old")).unwrap();
+
+        let dirs = planned_dirs(&jobs_for(src.path()), src.path());
+        let new_dirs = missing_dest_dirs(&dirs, src.path(), dst.path());
+        run(Args::parse_from([
+            "blit",
+            src.path().to_str().unwrap(),
+            dst.path().to_str().unwrap(),
+            "--mir",
+        ]))
+        .unwrap();
+
+        let mut stats = CopyStats::default();
+        count_dirs(&mut stats, &dirs, &new_dirs, src.path(), dst.path());
+        // old/new, fresh and fresh/deep; old and same were already there, the root is not counted
+        assert_eq!(stats.dirs_created, 3);
+        assert_eq!(stats.dirs_existing, 2);
+        let lines = summary_lines(&stats);
+        assert!(lines.contains(&"Directories created: 3 (2 already existed)".to_string()));
+
+        // A fresh destination root is not a created directory
+        let flat = tempfile::tempdir().unwrap();
+        touch(&flat.path().join("a.txt"), b"a");
+        assert!(planned_dirs(&jobs_for(flat.path()), flat.path()).is_empty());
+    }
+
+    #[test]
+    fn summary_reports_files_per_sec_and_throughput() {
+        let mut stats = CopyStats::default();
+        for _ in 0..40 {
+            stats.add_file(256 * 1024);
+        }
+        stats.elapsed = std::time::Duration::from_millis(500);
+        let lines = summary_lines(&stats);
+
+        let value = |prefix: &str| -> f64 {
+            let line = lines.iter().find(|l| l.starts_with(prefix)).unwrap();
+            line[prefix.len()..]
+                .split_whitespace()
+                .next()
+                .unwrap()
+                .parse()
+                .unwrap()
+        };
+        assert_eq!(value("Files/sec:"), 80.0);
+        assert_eq!(value("Throughput:"), 20.0);
+    }
+
+    #[test]
+    fn dry_run_line_shows_old_and_new_values() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        let s = src.path().join("f.txt");
+        let d = dst.path().join("f.txt");
+        touch(&s, b"new contents");
+        touch(&d, b"old");
+        let old_time = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&d, old_time).unwrap();
+
+        let line = dry_run_line(&s, &d);
+        assert!(line.contains("update: 3 → 12 bytes"), "{}", line);
+        let old_str = chrono::DateTime::<chrono::Local>::from(
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000),
+        )
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+        assert!(line.contains(&format!("{} → ", old_str)), "{}", line);
+
+        let fresh = dry_run_line(&s, &dst.path().join("missing.txt"));
+        assert!(fresh.contains("new: 12 bytes"), "{}", fresh);
+    }
+
+    #[test]
+    fn dry_run_total_counts_only_planned_files() {
+        let job = |size| CopyJob {
+            entry: FileEntry {
+                path: PathBuf::from("f"),
+                size,
+                is_directory: false,
+            },
+        };
+        let changed = [job(1_073_741_824), job(536_870_912)];
+        let planned: Vec<&CopyJob> = changed.iter().collect();
+        assert_eq!(
+            dry_run_total_line(&planned),
+            "Would transfer 2 files, 1.50 GB"
+        );
+        assert_eq!(dry_run_total_line(&[]), "Would transfer 0 files, 0.00 GB");
+    }
+
+    #[test]
+    fn plan_text_is_sorted_and_stable() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("b/new.txt"), b"n");
+        touch(&src.path().join("a.txt"), b"changed");
+        touch(&dst.path().join("a.txt"), b"old");
+        touch(&dst.path().join("gone/extra.txt"), b"x");
+
+        let render = || {
+            let mut jobs = jobs_for(src.path());
+            // Enumeration order must not leak into the plan
+            jobs.reverse();
+            let planned: Vec<&CopyJob> = jobs.iter().collect();
+            let deletions =
+                plan_mirror_deletion(src.path(), dst.path(), &FileFilter::default()).unwrap();
+            plan_text(&planned, src.path(), dst.path(), Some(&deletions))
+        };
+
+        let first = render();
+        assert_eq!(first, "~ a.txt\n+ b/new.txt\n- gone/extra.txt\n");
+        assert_eq!(first, render());
+    }
+
+    #[test]
+    fn checksum_compare_skips_hashing_size_mismatches() {
+        let entry = |path: &str, size| FileEntry {
+            path: PathBuf::from(path),
+            size,
+            is_directory: false,
+        };
+        let mut hashed = Vec::new();
+        let mut hash = |p: &Path| -> Result<[u8; 32]> {
+            hashed.push(p.to_path_buf());
+            Ok([0u8; 32])
+        };
+
+        assert!(pair_differs(&entry("a", 1), &entry("b", 2), true, &mut hash).unwrap());
+        assert!(!pair_differs(&entry("c", 5), &entry("d", 5), true, &mut hash).unwrap());
+        assert_eq!(hashed, vec![PathBuf::from("c"), PathBuf::from("d")]);
+    }
+
+    #[test]
+    fn concurrent_scans_return_both_trees() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        for i in 0..50 {
+            touch(&src.path().join(format!("d{}/s{}.txt", i % 5, i)), b"s");
+        }
+        touch(&src.path().join("skip/ignored.txt"), b"s");
+        for i in 0..30 {
+            touch(&dst.path().join(format!("d{}/t{}.txt", i % 3, i)), b"t");
+        }
+        touch(&dst.path().join("skip/kept.txt"), b"t");
+
+        let filter = FileFilter {
+            exclude_dirs: vec!["skip".into()],
+            ..Default::default()
+        };
+        let (left, right) = scan_source_and_destination(src.path(), &filter, dst.path()).unwrap();
+        assert_eq!(left.len(), 50);
+        assert!(left.iter().all(|e| e.path.starts_with(src.path())));
+        // The destination scan is unfiltered so excluded dirs are still seen
+        assert_eq!(right.len(), 31);
+        assert!(right.iter().all(|e| e.path.starts_with(dst.path())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserve_mode_keeps_setuid_setgid_bits() {
+        use std::os::unix::fs::PermissionsExt;
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        let tool = src.path().join("bin/tool");
+        touch(&tool, b"#!/bin/sh\n");
+        let set_mode = |p: &Path, mode: u32| {
+            std::fs::set_permissions(p, std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+        let mode_of = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+        let mirror = || {
+            run(Args::parse_from([
+                "blit",
+                "--mir",
+                "--preserve-mode",
+                src.path().to_str().unwrap(),
+                dst.path().to_str().unwrap(),
+            ]))
+            .unwrap()
+        };
+
+        set_mode(&tool, 0o2755);
+        mirror();
+        let copied = dst.path().join("bin/tool");
+        assert_eq!(mode_of(&copied), 0o2755);
+
+        // Mode-only change on an up-to-date file: fixed without a recopy
+        set_mode(&tool, 0o4750);
+        mirror();
+        assert_eq!(mode_of(&copied), 0o4750);
+    }
+
+    #[test]
+    fn log_file_inside_destination_survives_mirror() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"a");
+        touch(&dst.path().join("stale.txt"), b"stale");
+        let log = dst.path().join("logs/blit.log");
+        let mirror = || {
+            run(Args::parse_from([
+                "blit",
+                "--mir",
+                "--log-file",
+                log.to_str().unwrap(),
+                src.path().to_str().unwrap(),
+                dst.path().to_str().unwrap(),
+            ]))
+        };
+
+        mirror().unwrap();
+        // Again, now that the log already exists when the destination is scanned
+        mirror().unwrap();
+        assert!(log.exists());
+        assert!(dst.path().join("a.txt").exists());
+        assert!(!dst.path().join("stale.txt").exists());
+
+        // Neither the log nor the directory holding it is planned for deletion
+        let protect_log = FileFilter {
+            protected_files: vec![log.clone()],
+            ..Default::default()
+        };
+        let (files, dirs) = plan_mirror_deletion(src.path(), dst.path(), &protect_log).unwrap();
+        assert!(files.is_empty(), "{:?}", files);
+        assert!(dirs.is_empty(), "{:?}", dirs);
+        // A log inside the source is not copied either
+        let in_source = FileFilter {
+            protected_files: vec![src.path().join("a.txt")],
+            ..Default::default()
+        };
+        assert!(enumerate_directory_filtered(src.path(), &in_source)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn nested_destination_is_excluded_from_source_scan() {
+        let src = tempfile::tempdir().unwrap();
+        touch(&src.path().join("a.txt"), b"a");
+        touch(&src.path().join("backup/old.txt"), b"old");
+
+        let nested = destination_inside_source(src.path(), &src.path().join("backup"))
+            .unwrap()
+            .expect("nested destination detected");
+        assert_eq!(nested, src.path().join("backup"));
+
+        // Not-yet-existing nested destinations are detected too
+        assert!(
+            destination_inside_source(src.path(), &src.path().join("new/deeper"))
+                .unwrap()
+                .is_some()
+        );
+
+        let filter = FileFilter {
+            exclude_paths: vec![nested],
+            ..Default::default()
+        };
+        let entries = enumerate_directory_filtered(src.path(), &filter).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].path.ends_with("a.txt"));
+    }
+
+    #[test]
+    fn identical_source_and_destination_is_rejected() {
+        let src = tempfile::tempdir().unwrap();
+        let same = src.path().join(".");
+        assert!(destination_inside_source(src.path(), &same).is_err());
+
+        let other = tempfile::tempdir().unwrap();
+        assert!(destination_inside_source(src.path(), other.path())
+            .unwrap()
+            .is_none());
+    }
+}
@@ -228,7 +228,6 @@ pub mod server {
                 }
                 // Prepare/resize file and set mtime (idempotent). Payload: nlen u16 | name | size u64 | mtime i64
                 fids::SET_ATTR => {
-                    use std::io::Write as _;
                     if payload.len() < 2 + 8 + 8 { anyhow::bail!("bad SET_ATTR"); }
                     let nlen = u16::from_le_bytes([payload[0], payload[1]]) as usize;
                     if payload.len() < 2 + nlen + 8 + 8 { anyhow::bail!("bad SET_ATTR len"); }
@@ -239,7 +238,7 @@ pub mod server {
                     let mtime = i64::from_le_bytes(payload[off..off+8].try_into().unwrap());
                     let dst = base_dir.join(name);
                     if let Some(parent) = dst.parent() { std::fs::create_dir_all(parent).ok(); }
-                    let f = std::fs::OpenOptions::new().create(true).write(true).open(&dst)
+                    let f = std::fs::OpenOptions::new().create(true).truncate(false).write(true).open(&dst)
                         .with_context(|| format!("open {}", dst.display()))?;
                     f.set_len(size).context("set file length")?;
                     let ft = filetime::FileTime::from_unix_time(mtime, 0);
@@ -329,19 +328,6 @@ pub mod client {
     use tokio::time::{timeout, Duration};
     use tokio_rustls::{client::TlsStream as ClientTlsStream, TlsConnector};
 
-    #[inline]
-    async fn write_all_timed(stream: &mut TcpStream, buf: &[u8], ms: u64) -> Result<()> {
-        match timeout(Duration::from_millis(ms), async {
-            stream.write_all(buf).await
-        })
-        .await
-        {
-            Ok(Ok(_)) => Ok(()),
-            Ok(Err(e)) => Err(e.into()),
-            Err(_) => anyhow::bail!("raw body write timeout ({} ms)", ms),
-        }
-    }
-
     pub async fn connect(host: &str, port: u16) -> Result<TcpStream> {
         let addr = format!("{}:{}", host, port);
         let stream = TcpStream::connect(&addr)
@@ -867,8 +853,7 @@ pub mod client {
         let filter = crate::fs_enum::FileFilter {
             exclude_files: args.exclude_files.clone(),
             exclude_dirs: args.exclude_dirs.clone(),
-            min_size: None,
-            max_size: None,
+            ..Default::default()
         };
         let all_files = crate::fs_enum::enumerate_directory_filtered(src_root, &filter)?;
         let files_needed: Vec<_> = all_files
@@ -1120,8 +1105,7 @@ pub mod client {
         let filter = crate::fs_enum::FileFilter {
             exclude_files: args.exclude_files.clone(),
             exclude_dirs: args.exclude_dirs.clone(),
-            min_size: None,
-            max_size: None,
+            ..Default::default()
         };
         let entries = crate::fs_enum::enumerate_directory_filtered(dest_root, &filter)?;
         use std::time::UNIX_EPOCH;
@@ -305,6 +305,15 @@ pub fn tar_stream_transfer_list(
         .join()
         .map_err(|_| anyhow::anyhow!("Unpacker thread panicked"))??;
 
+    // Tar headers only hold whole seconds; put the full-precision mtime back so nanosecond
+    // destinations do not look older than their source on the next run
+    for (src_path, tar_rel_path) in files {
+        if let Ok(metadata) = src_path.metadata() {
+            let mtime = filetime::FileTime::from_last_modification_time(&metadata);
+            filetime::set_file_mtime(dest.join(tar_rel_path), mtime)?;
+        }
+    }
+
     if let Some(pb) = progress {
         pb.finish_with_message(format!(
             "Streamed {} files ({} MB)",
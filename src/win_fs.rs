@@ -57,7 +57,7 @@ pub fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
         use windows::Win32::Storage::FileSystem::{
             CreateSymbolicLinkW, SYMBOLIC_LINK_FLAGS, SYMBOLIC_LINK_FLAG_DIRECTORY,
         };
-        let flags: SYMBOLIC_LINK_FLAGS = if target.is_dir() {
+        let flags: SYMBOLIC_LINK_FLAGS = if link_target_is_dir(target, link) {
             SYMBOLIC_LINK_FLAG_DIRECTORY
         } else {
             SYMBOLIC_LINK_FLAGS(0)
@@ -75,6 +75,21 @@ pub fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
     }
 }
 
+/// Determines whether a symlink target refers to a directory.
+///
+/// Relative targets are resolved against the link's own directory (as the OS will when the link
+/// is followed), not the process working directory. The link itself still stores `target` verbatim.
+fn link_target_is_dir(target: &Path, link: &Path) -> bool {
+    if target.is_relative() {
+        match link.parent() {
+            Some(parent) => parent.join(target).is_dir(),
+            None => target.is_dir(),
+        }
+    } else {
+        target.is_dir()
+    }
+}
+
 /// Compares two relative paths case-insensitively, which is important on Windows.
 ///
 /// # Arguments
@@ -167,3 +182,28 @@ pub fn clear_readonly_recursive(path: &Path) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_sibling_dir_target_resolves_from_link_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dest = tmp.path().join("dest");
+        fs::create_dir_all(dest.join("sibling")).unwrap();
+        let link = dest.join("link");
+        let target = Path::new("sibling");
+
+        // Type detection uses the link's directory, not the current working directory
+        assert!(link_target_is_dir(target, &link));
+        assert!(!link_target_is_dir(Path::new("missing"), &link));
+
+        if !has_symlink_privilege() {
+            return;
+        }
+        create_symlink(target, &link).unwrap();
+        assert_eq!(fs::read_link(&link).unwrap(), target);
+        assert!(link.is_dir());
+    }
+}